@@ -0,0 +1,253 @@
+use crate::prelude::{HistoryRevisionInfo, RevisionId, Timestamp, UserInfo};
+use serde::{Deserialize, Serialize};
+use similar::{Algorithm, DiffTag, capture_diff_slices};
+use std::collections::{BTreeMap, HashSet};
+
+/// A revision that introduced at least one line of a `Blame`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BlameRevision {
+    pub id: RevisionId,
+    pub user: UserInfo,
+    pub timestamp: Timestamp,
+    pub comment: String,
+}
+
+impl From<&HistoryRevisionInfo> for BlameRevision {
+    fn from(revision: &HistoryRevisionInfo) -> Self {
+        Self {
+            id: revision.id,
+            user: revision.user.to_owned(),
+            timestamp: revision.timestamp.to_owned(),
+            comment: revision.comment.to_owned(),
+        }
+    }
+}
+
+/// A line of wikitext, and the revision that introduced it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlameLine {
+    pub text: String,
+    pub revision: RevisionId,
+}
+
+/// A run of consecutive lines introduced by the same revision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlameSpan {
+    /// Index of the first line of the span
+    pub first_line: usize,
+    /// Index after the last line of the span
+    pub end_line: usize,
+    /// Byte offset of the span in the wikitext
+    pub offset: usize,
+    /// Length of the span in bytes, excluding the final line break
+    pub length: usize,
+    pub revision: RevisionId,
+}
+
+/// Line-level authorship of the wikitext of a revision.
+/// A `Blame` can be serialized, cached by its revision ID, and later
+/// brought up to date with `Blame::apply` or `Page::update_blame`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Blame {
+    /// The revision the blame is for
+    pub revision: RevisionId,
+    pub lines: Vec<BlameLine>,
+    /// The revisions that introduced the lines, by revision ID
+    pub revisions: BTreeMap<RevisionId, BlameRevision>,
+    /// Revisions whose content could not be retrieved, eg deleted or suppressed revisions.
+    /// Their changes are attributed to the next revision that could be retrieved.
+    #[serde(default)]
+    pub skipped: Vec<RevisionId>,
+}
+
+impl Blame {
+    /// Creates a blame for the first revision of a page, attributing all lines to it.
+    pub fn new(revision: &HistoryRevisionInfo, wikitext: &str) -> Self {
+        let lines = wikitext
+            .split('\n')
+            .map(|text| BlameLine {
+                text: text.to_string(),
+                revision: revision.id,
+            })
+            .collect();
+        Self {
+            revision: revision.id,
+            lines,
+            revisions: [(revision.id, BlameRevision::from(revision))]
+                .into_iter()
+                .collect(),
+            skipped: vec![],
+        }
+    }
+
+    /// Returns the blame for the next revision, given its wikitext.
+    /// Unchanged lines keep their attribution, new and changed lines are attributed to `revision`.
+    pub fn apply(&self, revision: &HistoryRevisionInfo, wikitext: &str) -> Self {
+        let old_texts: Vec<&str> = self.lines.iter().map(|line| line.text.as_str()).collect();
+        let new_texts: Vec<&str> = wikitext.split('\n').collect();
+        let mut lines = vec![];
+        for op in capture_diff_slices(Algorithm::Myers, &old_texts, &new_texts) {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            match tag {
+                DiffTag::Equal => lines.extend_from_slice(&self.lines[old_range]),
+                DiffTag::Delete => {}
+                DiffTag::Insert | DiffTag::Replace => {
+                    lines.extend(new_texts[new_range].iter().map(|text| BlameLine {
+                        text: text.to_string(),
+                        revision: revision.id,
+                    }));
+                }
+            }
+        }
+        let mut revisions = self.revisions.clone();
+        revisions.insert(revision.id, BlameRevision::from(revision));
+        let line_revisions: HashSet<RevisionId> = lines.iter().map(|line| line.revision).collect();
+        revisions.retain(|id, _| line_revisions.contains(id));
+        Self {
+            revision: revision.id,
+            lines,
+            revisions,
+            skipped: self.skipped.clone(),
+        }
+    }
+
+    /// Computes the blame from a sequence of revisions and their wikitext, oldest first.
+    /// Returns `None` if there are no revisions.
+    pub fn from_revisions<'a, I>(revisions: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (&'a HistoryRevisionInfo, &'a str)>,
+    {
+        revisions
+            .into_iter()
+            .fold(None, |blame: Option<Self>, (revision, wikitext)| {
+                Some(blame.map_or_else(
+                    || Self::new(revision, wikitext),
+                    |blame| blame.apply(revision, wikitext),
+                ))
+            })
+    }
+
+    /// Returns the wikitext of the blamed revision.
+    pub fn wikitext(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the revision that introduced the line with the given index.
+    pub fn line_revision(&self, line: usize) -> Option<&BlameRevision> {
+        self.revisions.get(&self.lines.get(line)?.revision)
+    }
+
+    /// Returns runs of consecutive lines that were introduced by the same revision.
+    pub fn spans(&self) -> Vec<BlameSpan> {
+        let mut ret: Vec<BlameSpan> = vec![];
+        let mut offset = 0;
+        for (index, line) in self.lines.iter().enumerate() {
+            match ret.last_mut() {
+                Some(span) if span.revision == line.revision => {
+                    span.end_line = index + 1;
+                    span.length += 1 + line.text.len();
+                }
+                _ => ret.push(BlameSpan {
+                    first_line: index,
+                    end_line: index + 1,
+                    offset,
+                    length: line.text.len(),
+                    revision: line.revision,
+                }),
+            }
+            offset += line.text.len() + 1;
+        }
+        ret
+    }
+
+    /// Returns the spans that overlap the given byte range of the wikitext.
+    pub fn spans_in(&self, start: usize, end: usize) -> Vec<BlameSpan> {
+        self.spans()
+            .into_iter()
+            .filter(|span| span.offset < end && span.offset + span.length >= start)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn revision(id: usize, user: &str) -> HistoryRevisionInfo {
+        serde_json::from_value(json!({
+            "id": id,
+            "size": 0,
+            "delta": 0,
+            "comment": format!("edit {id}"),
+            "minor": false,
+            "timestamp": "2025-10-15T07:59:08Z",
+            "user": { "id": null, "name": user }
+        }))
+        .expect("Failed to parse revision")
+    }
+
+    fn test_blame() -> Blame {
+        let revisions = [
+            (revision(1, "Alice"), "Intro\nFirst paragraph."),
+            (
+                revision(2, "Bob"),
+                "Intro\nFirst paragraph.\nSecond paragraph.",
+            ),
+            (
+                revision(3, "Carol"),
+                "Intro!\nFirst paragraph.\nSecond paragraph.",
+            ),
+            (revision(4, "Dave"), "Intro!\nSecond paragraph."),
+        ];
+        Blame::from_revisions(revisions.iter().map(|(r, text)| (r, *text)))
+            .expect("Blame should exist")
+    }
+
+    #[test]
+    fn test_from_revisions() {
+        let blame = test_blame();
+        assert_eq!(blame.revision, RevisionId(4));
+        assert_eq!(blame.wikitext(), "Intro!\nSecond paragraph.");
+        let revisions: Vec<_> = blame.lines.iter().map(|line| line.revision).collect();
+        assert_eq!(revisions, [RevisionId(3), RevisionId(2)]);
+        assert_eq!(
+            blame.line_revision(1).map(|r| r.user.name.as_str()),
+            Some("Bob")
+        );
+        // Revisions without remaining lines are dropped
+        assert_eq!(
+            blame.revisions.keys().copied().collect::<Vec<_>>(),
+            [RevisionId(2), RevisionId(3)]
+        );
+        assert!(Blame::from_revisions(vec![]).is_none());
+    }
+
+    #[test]
+    fn test_spans() {
+        let blame =
+            Blame::new(&revision(1, "Alice"), "a\nb").apply(&revision(2, "Bob"), "a\nb\nc\nd");
+        let spans = blame.spans();
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[1].first_line, 2);
+        assert_eq!(spans[1].end_line, 4);
+        assert_eq!(spans[1].offset, 4);
+        assert_eq!(spans[1].length, 3);
+        assert_eq!(blame.spans_in(5, 6), vec![spans[1]]);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let blame = test_blame();
+        let json = serde_json::to_string(&blame).expect("Failed to serialize");
+        let restored: Blame = serde_json::from_str(&json).expect("Failed to deserialize");
+        assert_eq!(restored.lines, blame.lines);
+        assert_eq!(restored.revision, RevisionId(4));
+        let updated = restored.apply(&revision(5, "Eve"), "Intro!\nSecond paragraph.\nThird.");
+        assert_eq!(updated.lines[2].revision, RevisionId(5));
+    }
+}
@@ -0,0 +1,35 @@
+use crate::error::RestApiError;
+use futures::stream::{self, BoxStream, StreamExt};
+use std::future::Future;
+
+/// A stream of `(key, result)` pairs, as returned by the bulk retrieval functions.
+pub type BulkStream<'a, K, T> = BoxStream<'a, (K, Result<T, RestApiError>)>;
+
+/// Runs `f` for every key, with at most `concurrency` requests in flight at any time.
+/// Results are yielded as `(key, result)` pairs, in completion order,
+/// or in input order if `preserve_order` is set.
+/// A failing request does not abort the remaining ones.
+pub(crate) fn run_concurrent<'a, K, T, F, Fut>(
+    keys: Vec<K>,
+    concurrency: usize,
+    preserve_order: bool,
+    f: F,
+) -> BulkStream<'a, K, T>
+where
+    K: Clone + Send + 'a,
+    T: Send + 'a,
+    F: Fn(K) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<T, RestApiError>> + Send + 'a,
+{
+    let futures = stream::iter(keys).map(move |key| {
+        let future = f(key.clone());
+        async move { (key, future.await) }
+    });
+    // A buffer size of 0 would never poll anything
+    let concurrency = concurrency.max(1);
+    if preserve_order {
+        futures.buffered(concurrency).boxed()
+    } else {
+        futures.buffer_unordered(concurrency).boxed()
+    }
+}
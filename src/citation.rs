@@ -0,0 +1,553 @@
+use crate::wikitext::parse_attributes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A citation template call, eg `{{Cite web |url=... |title=...}}`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CitationTemplate {
+    /// The template name as written, trimmed, eg `Cite web`
+    pub name: String,
+    /// The parameters as written, in order. Unnamed parameters are numbered from `1`.
+    pub params: Vec<(String, String)>,
+}
+
+impl CitationTemplate {
+    /// Returns the trimmed value of the first parameter named `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Checks if a template name is a citation template, eg `Cite web`, `cite book` or `Citation`.
+    pub fn is_citation_template(name: &str) -> bool {
+        let name = name.trim().to_lowercase().replace('_', " ");
+        let name = name.strip_prefix("template:").unwrap_or(&name);
+        name.starts_with("cite ") || name == "citation" || name.starts_with("citation/")
+    }
+}
+
+/// A reference (`<ref>`), with its citation templates and identifiers.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    /// The name of a named reference
+    pub name: Option<String>,
+    pub group: Option<String>,
+    /// The rendered text when extracted from HTML, or the wikitext between `<ref>` and `</ref>`
+    pub text: String,
+    pub templates: Vec<CitationTemplate>,
+    /// DOIs from `doi` parameters and the text, eg `10.1145/3418295`
+    pub dois: Vec<String>,
+    /// ISBNs from `isbn` parameters and the text, without hyphens
+    pub isbns: Vec<String>,
+    /// URLs from URL parameters, external links and the text
+    pub urls: Vec<String>,
+}
+
+impl Citation {
+    /// Extracts all `<ref>` tags from wikitext, in order.
+    /// Reused named references (`<ref name="x"/>`) get the content of the reference that defines them.
+    pub fn from_wikitext(wikitext: &str) -> Vec<Self> {
+        let mut ret: Vec<Self> = Self::find_refs(wikitext)
+            .into_iter()
+            .map(|(attrs, content)| {
+                let content = content.trim();
+                let mut citation = Self {
+                    name: attrs.get("name").cloned(),
+                    group: attrs.get("group").cloned(),
+                    text: content.to_string(),
+                    templates: parse_templates(content),
+                    ..Default::default()
+                };
+                citation.add_identifiers(content);
+                citation
+            })
+            .collect();
+        Self::fill_reused(&mut ret);
+        ret
+    }
+
+    /// Creates a citation from a reference in Parsoid HTML, with the citation templates from its `data-mw`.
+    #[cfg(feature = "html")]
+    pub(crate) fn from_reference(reference: &crate::html::Reference) -> Self {
+        use crate::html::{
+            EXT_LINK_SELECTOR, TRANSCLUSION_SELECTOR, TransclusionData, TransclusionPart,
+            visible_text,
+        };
+        let fragment = scraper::Html::parse_fragment(&reference.html);
+        let templates = fragment
+            .select(&TRANSCLUSION_SELECTOR)
+            .filter_map(|element| {
+                serde_json::from_str::<TransclusionData>(element.attr("data-mw")?).ok()
+            })
+            .flat_map(|data| data.parts)
+            .filter_map(|part| match part {
+                TransclusionPart::Template { template }
+                    if CitationTemplate::is_citation_template(template.name()) =>
+                {
+                    Some(CitationTemplate {
+                        name: template.name().to_string(),
+                        params: template
+                            .params
+                            .0
+                            .into_iter()
+                            .map(|(key, param)| (key, param.wt.trim().to_string()))
+                            .collect(),
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        let text = visible_text(&fragment.root_element());
+        let mut ret = Self {
+            name: reference.name.clone(),
+            group: reference.group.clone(),
+            text: text.clone(),
+            templates,
+            ..Default::default()
+        };
+        for link in fragment.select(&EXT_LINK_SELECTOR) {
+            if let Some(href) = link.attr("href").filter(|href| is_url(href)) {
+                push_unique(&mut ret.urls, href.to_string());
+            }
+        }
+        ret.add_identifiers(&text);
+        ret
+    }
+
+    /// Gives reused named references, which have no content, the content of the reference that defines them.
+    pub(crate) fn fill_reused(citations: &mut [Self]) {
+        let defined: HashMap<(Option<String>, String), Self> = citations
+            .iter()
+            .filter(|citation| !citation.text.is_empty())
+            .filter_map(|citation| {
+                let name = citation.name.clone()?;
+                Some(((citation.group.clone(), name), citation.clone()))
+            })
+            .collect();
+        for citation in citations.iter_mut().filter(|c| c.text.is_empty()) {
+            let Some(name) = citation.name.clone() else {
+                continue;
+            };
+            if let Some(definition) = defined.get(&(citation.group.clone(), name)) {
+                citation.text.clone_from(&definition.text);
+                citation.templates.clone_from(&definition.templates);
+                citation.dois.clone_from(&definition.dois);
+                citation.isbns.clone_from(&definition.isbns);
+                citation.urls.clone_from(&definition.urls);
+            }
+        }
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Adds DOIs, ISBNs and URLs from the citation templates and `text`.
+    fn add_identifiers(&mut self, text: &str) {
+        for template in &self.templates {
+            for (key, value) in &template.params {
+                let key = key.to_lowercase();
+                if value.is_empty() {
+                    continue;
+                }
+                if key == "doi" {
+                    push_unique(&mut self.dois, value.clone());
+                } else if key == "isbn" || key == "isbn13" {
+                    push_unique(&mut self.isbns, value.replace(['-', ' '], ""));
+                } else if key.ends_with("url") && is_url(value) {
+                    push_unique(&mut self.urls, value.clone());
+                }
+            }
+        }
+        for doi in find_dois(text) {
+            push_unique(&mut self.dois, doi);
+        }
+        for isbn in find_isbns(text) {
+            push_unique(&mut self.isbns, isbn);
+        }
+        for url in find_urls(text) {
+            push_unique(&mut self.urls, url);
+        }
+    }
+
+    /// Finds all `<ref>` tags outside of comments and `<nowiki>`, returning their attributes and content.
+    fn find_refs(wikitext: &str) -> Vec<(HashMap<String, String>, &str)> {
+        let lowercase = wikitext.to_ascii_lowercase();
+        let mut ret = vec![];
+        let mut pos = 0;
+        while let Some(start) = lowercase[pos..].find('<').map(|start| pos + start) {
+            let tail = &lowercase[start..];
+            if tail.starts_with("<!--") {
+                pos = tail
+                    .find("-->")
+                    .map_or(lowercase.len(), |end| start + end + 3);
+                continue;
+            }
+            if let Some(after) = tail.strip_prefix("<nowiki")
+                && after.starts_with(['>', ' '])
+            {
+                pos = tail
+                    .find("</nowiki>")
+                    .map_or(lowercase.len(), |end| start + end + 9);
+                continue;
+            }
+            let is_ref = tail
+                .strip_prefix("<ref")
+                .is_some_and(|after| after.starts_with(['>', '/', ' ', '\t', '\n']));
+            let Some(tag_end) = tail.find('>').filter(|_| is_ref) else {
+                pos = start + 1;
+                continue;
+            };
+            let tag = &wikitext[start + 4..start + tag_end];
+            let attrs = parse_attributes(tag.trim_end_matches('/'));
+            let content_start = start + tag_end + 1;
+            if tag.ends_with('/') {
+                ret.push((attrs, ""));
+                pos = content_start;
+                continue;
+            }
+            let Some(close) = lowercase[content_start..].find("</ref") else {
+                break;
+            };
+            let close = content_start + close;
+            ret.push((attrs, &wikitext[content_start..close]));
+            pos = lowercase[close..]
+                .find('>')
+                .map_or(lowercase.len(), |end| close + end + 1);
+        }
+        ret
+    }
+}
+
+/// Finds the citation templates in wikitext, including those nested in other templates.
+fn parse_templates(wikitext: &str) -> Vec<CitationTemplate> {
+    let mut ret = vec![];
+    let mut pos = 0;
+    while let Some(start) = wikitext[pos..].find("{{").map(|start| pos + start) {
+        let Some(end) = matching_braces(wikitext, start) else {
+            break;
+        };
+        let inner = &wikitext[start + 2..end - 2];
+        let parts = split_top_level(inner);
+        let name = parts.first().map_or("", |name| name.trim());
+        if CitationTemplate::is_citation_template(name) {
+            let mut positional = 0;
+            let params = parts[1..]
+                .iter()
+                .map(|part| match part.split_once('=') {
+                    Some((key, value)) if !key.contains(['{', '[']) => {
+                        (key.trim().to_string(), value.trim().to_string())
+                    }
+                    _ => {
+                        positional += 1;
+                        (positional.to_string(), part.trim().to_string())
+                    }
+                })
+                .collect();
+            ret.push(CitationTemplate {
+                name: name.to_string(),
+                params,
+            });
+        } else {
+            ret.extend(parse_templates(inner));
+        }
+        pos = end;
+    }
+    ret
+}
+
+/// Returns the position after the `}}` matching the `{{` at `start`.
+fn matching_braces(wikitext: &str, start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut pos = start;
+    while pos < wikitext.len() {
+        let tail = &wikitext[pos..];
+        if tail.starts_with("{{") {
+            depth += 1;
+            pos += 2;
+        } else if tail.starts_with("}}") {
+            depth -= 1;
+            pos += 2;
+            if depth == 0 {
+                return Some(pos);
+            }
+        } else {
+            pos += tail.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    None
+}
+
+/// Splits template contents at `|` outside of nested templates and links.
+fn split_top_level(inner: &str) -> Vec<&str> {
+    let mut ret = vec![];
+    let mut depth = 0_usize;
+    let mut part_start = 0;
+    let mut pos = 0;
+    while pos < inner.len() {
+        let tail = &inner[pos..];
+        if tail.starts_with("{{") || tail.starts_with("[[") {
+            depth += 1;
+            pos += 2;
+        } else if tail.starts_with("}}") || tail.starts_with("]]") {
+            depth = depth.saturating_sub(1);
+            pos += 2;
+        } else {
+            if depth == 0 && tail.starts_with('|') {
+                ret.push(&inner[part_start..pos]);
+                part_start = pos + 1;
+            }
+            pos += tail.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    ret.push(&inner[part_start..]);
+    ret
+}
+
+/// Checks if a string is an absolute or protocol-relative HTTP(S) URL.
+fn is_url(text: &str) -> bool {
+    let text = text.trim();
+    text.starts_with("http://") || text.starts_with("https://") || text.starts_with("//")
+}
+
+/// Finds `http://` and `https://` URLs in text.
+fn find_urls(text: &str) -> Vec<String> {
+    let mut ret = vec![];
+    let mut pos = 0;
+    while let Some(start) = text[pos..].find("http").map(|start| pos + start) {
+        let tail = &text[start..];
+        if !tail.starts_with("http://") && !tail.starts_with("https://") {
+            pos = start + 4;
+            continue;
+        }
+        let end = tail
+            .find(|c: char| c.is_whitespace() || "|[]{}<>\"".contains(c))
+            .unwrap_or(tail.len());
+        let url = tail[..end].trim_end_matches(['.', ',', ';', ':', ')', '\'']);
+        if url.len() > "https://".len() {
+            ret.push(url.to_string());
+        }
+        pos = start + end.max(4);
+    }
+    ret
+}
+
+/// Finds DOIs (`10.` followed by a registrant code, `/` and a suffix) in text.
+fn find_dois(text: &str) -> Vec<String> {
+    let mut ret = vec![];
+    let mut pos = 0;
+    while let Some(start) = text[pos..].find("10.").map(|start| pos + start) {
+        pos = start + 3;
+        let preceded_by_word = text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '.');
+        if preceded_by_word {
+            continue;
+        }
+        let tail = &text[start + 3..];
+        let registrant = tail
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(tail.len());
+        if registrant < 4 || !tail[registrant..].starts_with('/') {
+            continue;
+        }
+        let suffix = &tail[registrant + 1..];
+        let end = suffix
+            .find(|c: char| c.is_whitespace() || "|[]{}<>\"".contains(c))
+            .unwrap_or(suffix.len());
+        let suffix = suffix[..end].trim_end_matches(['.', ',', ';', ':', ')', '\'']);
+        if suffix.is_empty() {
+            continue;
+        }
+        ret.push(format!("10.{}/{suffix}", &tail[..registrant]));
+        pos = start + 3 + registrant + 1 + end;
+    }
+    ret
+}
+
+/// Finds ISBNs following the text `ISBN` with a valid check digit, without hyphens.
+fn find_isbns(text: &str) -> Vec<String> {
+    let mut ret = vec![];
+    let mut pos = 0;
+    while let Some(start) = text[pos..].find("ISBN").map(|start| pos + start) {
+        pos = start + 4;
+        let tail = &text[pos..];
+        let tail = tail
+            .strip_prefix("-10")
+            .or_else(|| tail.strip_prefix("-13"))
+            .unwrap_or(tail)
+            .trim_start_matches([':', ' ', '\u{a0}']);
+        let mut digits = String::new();
+        for c in tail.chars() {
+            match c {
+                '0'..='9' => digits.push(c),
+                'X' | 'x' => {
+                    digits.push('X');
+                    break;
+                }
+                '-' | ' ' | '\u{a0}' => {}
+                _ => break,
+            }
+            if digits.len() == 13 {
+                break;
+            }
+        }
+        if is_valid_isbn13(&digits) {
+            ret.push(digits);
+        } else if digits.len() >= 10 && is_valid_isbn10(&digits[..10]) {
+            ret.push(digits[..10].to_string());
+        }
+    }
+    ret
+}
+
+/// Checks the length and check digit of an ISBN-10.
+fn is_valid_isbn10(isbn: &str) -> bool {
+    if isbn.len() != 10 {
+        return false;
+    }
+    let mut sum = 0;
+    for (weight, c) in (1..=10).rev().zip(isbn.chars()) {
+        let value = match c {
+            'X' if weight == 1 => 10,
+            _ => match c.to_digit(10) {
+                Some(digit) => digit,
+                None => return false,
+            },
+        };
+        sum += value * weight;
+    }
+    sum % 11 == 0
+}
+
+/// Checks the length, prefix and check digit of an ISBN-13.
+fn is_valid_isbn13(isbn: &str) -> bool {
+    if isbn.len() != 13 || !isbn.starts_with("97") {
+        return false;
+    }
+    let mut sum = 0;
+    for (i, c) in isbn.chars().enumerate() {
+        let Some(digit) = c.to_digit(10) else {
+            return false;
+        };
+        sum += if i % 2 == 0 { digit } else { digit * 3 };
+    }
+    sum % 10 == 0
+}
+
+/// Adds a value to a list, unless it is already there.
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_wikitext() -> String {
+        let text = std::fs::read_to_string("test_data/page_get.json").expect("Test file missing");
+        let json: serde_json::Value = serde_json::from_str(&text).expect("Failed to parse JSON");
+        json["source"].as_str().expect("Source missing").to_string()
+    }
+
+    #[test]
+    fn test_from_wikitext() {
+        let citations = Citation::from_wikitext(&test_wikitext());
+        assert_eq!(citations.len(), 193);
+        assert!(citations.iter().all(|citation| !citation.text.is_empty()));
+        let strom = citations
+            .iter()
+            .find(|citation| citation.name.as_deref() == Some("Strom1983"))
+            .expect("Citation missing");
+        let template = &strom.templates[0];
+        assert_eq!(template.name, "cite book");
+        assert_eq!(template.get("last1"), Some("Strom"));
+        assert_eq!(template.get("pages"), Some("276–284"));
+        assert_eq!(strom.dois, ["10.1145/567067.567093"]);
+        assert_eq!(strom.isbns, ["0897910907"]);
+        let cross_platform: Vec<&Citation> = citations
+            .iter()
+            .filter(|citation| citation.name.as_deref() == Some("CrossPlatform"))
+            .collect();
+        assert_eq!(cross_platform.len(), 2);
+        assert_eq!(cross_platform[0].templates, cross_platform[1].templates);
+        assert_eq!(
+            cross_platform[0].urls,
+            [
+                "https://doc.rust-lang.org/rustc/platform-support.html",
+                "https://web.archive.org/web/20220630164523/https://doc.rust-lang.org/rustc/platform-support.html"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_refs_and_templates() {
+        let wikitext = "A<ref name=a group='note'>{{Citation|Foo|title={{lang|de|Bar}}|url=https://example.org/x}}</ref> \
+            <!-- <ref>commented</ref> --><nowiki><ref>no</ref></nowiki>\
+            B<ref name=\"a\" group=note/> C<REF>See [https://example.com/y here], {{Sfn|X|2020}}, \
+            doi:10.1000/182. ISBN 978-0-306-40615-7 2019</REF>";
+        let citations = Citation::from_wikitext(wikitext);
+        assert_eq!(citations.len(), 3);
+        assert_eq!(citations[0].name.as_deref(), Some("a"));
+        assert_eq!(citations[0].group.as_deref(), Some("note"));
+        assert_eq!(
+            citations[0].templates[0].params,
+            [
+                ("1".to_string(), "Foo".to_string()),
+                ("title".to_string(), "{{lang|de|Bar}}".to_string()),
+                ("url".to_string(), "https://example.org/x".to_string()),
+            ]
+        );
+        assert_eq!(citations[1], citations[0]);
+        assert!(citations[2].templates.is_empty());
+        assert_eq!(citations[2].urls, ["https://example.com/y"]);
+        assert_eq!(citations[2].dois, ["10.1000/182"]);
+        assert_eq!(citations[2].isbns, ["9780306406157"]);
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(
+            find_dois("https://doi.org/10.1145/3418295, v1.10.1234/x"),
+            ["10.1145/3418295"]
+        );
+        assert_eq!(
+            find_isbns("ISBN 0-306-40615-2 1999, ISBN-13: 978-0-306-40615-8, ISBN 0-306-40615-X"),
+            ["0306406152"]
+        );
+        assert!(CitationTemplate::is_citation_template("Template:Cite_web"));
+        assert!(CitationTemplate::is_citation_template(" citation "));
+        assert!(!CitationTemplate::is_citation_template("Citation needed"));
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn test_from_html() {
+        let html =
+            std::fs::read_to_string("test_data/page_get_html.html").expect("Test file missing");
+        let citations = crate::html::ParsoidDocument::parse(&html).citations();
+        assert_eq!(citations.len(), 273);
+        let strom = citations
+            .iter()
+            .find(|citation| citation.name.as_deref() == Some("Strom1983"))
+            .expect("Citation missing");
+        assert!(strom.text.starts_with("Strom, Robert E. (1983)."));
+        assert_eq!(strom.dois, ["10.1145/567067.567093"]);
+        assert_eq!(strom.isbns, ["0897910907"]);
+        assert!(
+            strom
+                .urls
+                .contains(&"https://api.semanticscholar.org/CorpusID:6630704".to_string())
+        );
+        let ocaml = citations
+            .iter()
+            .find(|citation| citation.name.as_deref() == Some("OCamlCompiler"))
+            .expect("Citation missing");
+        assert_eq!(ocaml.templates[0].name, "Cite web");
+        assert_eq!(ocaml.templates[0].get("last"), Some("Hoare"));
+    }
+}
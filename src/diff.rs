@@ -0,0 +1,679 @@
+use crate::prelude::{
+    Diff, DiffInfo, DiffLineType, DiffOffset, DiffSection, DiffSections, HighlightRange,
+    HighlightType, RevisionId, WikitextSections,
+};
+use similar::{Algorithm, ChangeTag, DiffTag, TextDiff, capture_diff_slices};
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_CYAN: &str = "\x1b[36m";
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_RED_BACKGROUND: &str = "\x1b[41;97m";
+const ANSI_GREEN_BACKGROUND: &str = "\x1b[42;97m";
+
+/// The number of unchanged lines included around each change, as in `MediaWiki` diffs
+const DIFF_CONTEXT_LINES: usize = 2;
+
+/// The minimum word similarity for a deleted and an added line to be paired as a changed line
+const CHANGED_LINE_MIN_SIMILARITY: f32 = 0.5;
+
+/// A line of wikitext, and its byte offset
+#[derive(Clone, Copy, Debug)]
+struct Line<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+/// A part of a diff line, which is either unchanged, or highlighted as added or deleted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiffSegment<'a> {
+    pub text: &'a str,
+    pub highlight: Option<HighlightType>,
+}
+
+impl DiffInfo {
+    /// Splits the line text into unchanged and highlighted segments, according to the highlight ranges.
+    /// Ranges that do not fall on character boundaries are ignored.
+    pub fn segments(&self) -> Vec<DiffSegment<'_>> {
+        let mut ranges = self.highlight_ranges.clone();
+        ranges.sort_by_key(|range| range.start);
+        let mut ret = vec![];
+        let mut pos = 0;
+        for range in ranges {
+            let start = range.start.max(pos);
+            let end = (range.start + range.length).min(self.text.len());
+            let (Some(before), Some(highlighted)) =
+                (self.text.get(pos..start), self.text.get(start..end))
+            else {
+                continue;
+            };
+            if highlighted.is_empty() {
+                continue;
+            }
+            if !before.is_empty() {
+                ret.push(DiffSegment {
+                    text: before,
+                    highlight: None,
+                });
+            }
+            ret.push(DiffSegment {
+                text: highlighted,
+                highlight: Some(range.highlight_type()),
+            });
+            pos = end;
+        }
+        if let Some(rest) = self.text.get(pos..)
+            && (!rest.is_empty() || ret.is_empty())
+        {
+            ret.push(DiffSegment {
+                text: rest,
+                highlight: None,
+            });
+        }
+        ret
+    }
+
+    /// Returns the text of the line in the old revision, or `None` if the line does not exist there.
+    pub fn old_text(&self) -> Option<String> {
+        match self.line_type() {
+            DiffLineType::Added | DiffLineType::MovedTo => None,
+            _ => Some(self.text_without(HighlightType::Added)),
+        }
+    }
+
+    /// Returns the text of the line in the new revision, or `None` if the line does not exist there.
+    pub fn new_text(&self) -> Option<String> {
+        match self.line_type() {
+            DiffLineType::Deleted | DiffLineType::MovedFrom => None,
+            _ => Some(self.text_without(HighlightType::Deleted)),
+        }
+    }
+
+    /// Returns the text, without the segments highlighted with `highlight`.
+    fn text_without(&self, highlight: HighlightType) -> String {
+        self.segments()
+            .iter()
+            .filter(|segment| segment.highlight != Some(highlight))
+            .map(|segment| segment.text)
+            .collect()
+    }
+}
+
+impl Diff {
+    /// Computes a diff between two wikitexts locally, without using the compare endpoint.
+    /// The result has the same structure as the one returned by `Revision::get_compare`,
+    /// including word-level highlights for changed lines, and the sections of both texts.
+    /// Moved paragraphs are reported as deleted and added lines.
+    /// Revision IDs in `from` and `to` are set to 0.
+    pub fn from_wikitext(from: &str, to: &str) -> Self {
+        let old_lines = Self::split_lines(from);
+        let new_lines = Self::split_lines(to);
+        let old_texts: Vec<&str> = old_lines.iter().map(|line| line.text).collect();
+        let new_texts: Vec<&str> = new_lines.iter().map(|line| line.text).collect();
+        let mut lines = vec![];
+        for op in capture_diff_slices(Algorithm::Myers, &old_texts, &new_texts) {
+            let (tag, old_range, new_range) = op.as_tag_tuple();
+            match tag {
+                DiffTag::Equal => {
+                    for (old, new) in old_range.zip(new_range) {
+                        lines.push(Self::context_line(&old_lines[old], &new_lines[new], new));
+                    }
+                }
+                DiffTag::Delete | DiffTag::Insert | DiffTag::Replace => {
+                    let old = &old_lines[old_range];
+                    let new = &new_lines[new_range.clone()];
+                    for num in 0..old.len().max(new.len()) {
+                        let new_number = new_range.start + num;
+                        lines.append(&mut Self::replaced_lines(
+                            old.get(num),
+                            new.get(num),
+                            new_number,
+                        ));
+                    }
+                }
+            }
+        }
+        Self {
+            diff: Self::remove_distant_context(lines),
+            from: Self::diff_sections(from),
+            to: Self::diff_sections(to),
+        }
+    }
+
+    /// Renders the diff as unified diff text.
+    /// Hunk headers give the line number in the new revision, eg "@@ +528 @@",
+    /// as the `MediaWiki` diff does not contain line numbers for the old revision.
+    pub fn to_unified(&self) -> String {
+        let mut ret = String::new();
+        for hunk in self.hunks() {
+            ret += &Self::hunk_header(hunk);
+            ret.push('\n');
+            for line in hunk {
+                if let Some(text) = Self::unified_old_line(line) {
+                    ret += &format!("-{text}\n");
+                }
+                if let Some(text) = Self::unified_new_line(line) {
+                    ret += &format!("{text}\n");
+                }
+            }
+        }
+        ret
+    }
+
+    /// Renders the diff as unified diff text, colored with ANSI escape codes for terminal output.
+    /// Changed parts of modified lines are highlighted with a background color.
+    pub fn to_ansi(&self) -> String {
+        let mut ret = String::new();
+        for hunk in self.hunks() {
+            ret += &format!("{ANSI_CYAN}{}{ANSI_RESET}\n", Self::hunk_header(hunk));
+            for line in hunk {
+                match line.line_type() {
+                    DiffLineType::Added | DiffLineType::MovedTo => {
+                        ret += &format!("{ANSI_GREEN}+{}{ANSI_RESET}\n", line.text);
+                    }
+                    DiffLineType::Deleted | DiffLineType::MovedFrom => {
+                        ret += &format!("{ANSI_RED}-{}{ANSI_RESET}\n", line.text);
+                    }
+                    DiffLineType::Changed => {
+                        ret += &Self::ansi_changed_line(line, HighlightType::Deleted);
+                        ret += &Self::ansi_changed_line(line, HighlightType::Added);
+                    }
+                    DiffLineType::Context | DiffLineType::Unknown(_) => {
+                        ret += &format!(" {}\n", line.text);
+                    }
+                }
+            }
+        }
+        ret
+    }
+
+    /// Renders the diff as a side-by-side HTML table, using the CSS classes of the `MediaWiki` diff view.
+    pub fn to_side_by_side_html(&self) -> String {
+        let mut ret = String::from("<table class=\"diff\">\n");
+        for hunk in self.hunks() {
+            let line_number = hunk
+                .iter()
+                .find_map(|line| line.line_number)
+                .map(|line_number| format!("Line {line_number}:"))
+                .unwrap_or_default();
+            ret += &format!(
+                "<tr><td colspan=\"2\" class=\"diff-lineno\"></td><td colspan=\"2\" class=\"diff-lineno\">{line_number}</td></tr>\n"
+            );
+            for line in hunk {
+                let left = match line.line_type() {
+                    DiffLineType::Added | DiffLineType::MovedTo => {
+                        Self::html_empty_cell(HighlightType::Deleted)
+                    }
+                    DiffLineType::Context | DiffLineType::Unknown(_) => {
+                        Self::html_cell("", "diff-context", &html_escape(&line.text))
+                    }
+                    _ => Self::html_cell(
+                        "\u{2212}",
+                        "diff-deletedline",
+                        &Self::html_segments(line, HighlightType::Deleted),
+                    ),
+                };
+                let right = match line.line_type() {
+                    DiffLineType::Deleted | DiffLineType::MovedFrom => {
+                        Self::html_empty_cell(HighlightType::Added)
+                    }
+                    DiffLineType::Context | DiffLineType::Unknown(_) => {
+                        Self::html_cell("", "diff-context", &html_escape(&line.text))
+                    }
+                    _ => Self::html_cell(
+                        "+",
+                        "diff-addedline",
+                        &Self::html_segments(line, HighlightType::Added),
+                    ),
+                };
+                ret += &format!("<tr>{left}{right}</tr>\n");
+            }
+        }
+        ret += "</table>\n";
+        ret
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Splits text into lines, with their byte offsets. Empty text has no lines.
+    fn split_lines(text: &str) -> Vec<Line<'_>> {
+        if text.is_empty() {
+            return vec![];
+        }
+        let mut offset = 0;
+        text.split('\n')
+            .map(|text| {
+                let line = Line { text, offset };
+                offset += text.len() + 1;
+                line
+            })
+            .collect()
+    }
+
+    /// Returns an unchanged line.
+    fn context_line(old: &Line, new: &Line, new_index: usize) -> DiffInfo {
+        Self::diff_line(
+            DiffLineType::Context,
+            Some(new_index + 1),
+            Some(old.offset),
+            Some(new.offset),
+            new.text.to_string(),
+            vec![],
+        )
+    }
+
+    /// Returns the diff lines for an old line replaced by a new line, where either can be missing.
+    /// Similar lines are paired as a changed line, with word-level highlights.
+    fn replaced_lines(old: Option<&Line>, new: Option<&Line>, new_index: usize) -> Vec<DiffInfo> {
+        let deleted = |line: &Line| {
+            Self::diff_line(
+                DiffLineType::Deleted,
+                None,
+                Some(line.offset),
+                None,
+                line.text.to_string(),
+                vec![],
+            )
+        };
+        let added = |line: &Line| {
+            Self::diff_line(
+                DiffLineType::Added,
+                Some(new_index + 1),
+                None,
+                Some(line.offset),
+                line.text.to_string(),
+                vec![],
+            )
+        };
+        match (old, new) {
+            (Some(old), Some(new)) => {
+                let word_diff = TextDiff::from_words(old.text, new.text);
+                if word_diff.ratio() < CHANGED_LINE_MIN_SIMILARITY {
+                    return vec![deleted(old), added(new)];
+                }
+                let mut text = String::new();
+                let mut ranges: Vec<HighlightRange> = vec![];
+                for change in word_diff.iter_all_changes() {
+                    let type_id = match change.tag() {
+                        ChangeTag::Equal => None,
+                        ChangeTag::Insert => Some(0),
+                        ChangeTag::Delete => Some(1),
+                    };
+                    if let Some(type_id) = type_id {
+                        match ranges.last_mut() {
+                            Some(last)
+                                if last.type_id == type_id
+                                    && last.start + last.length == text.len() =>
+                            {
+                                last.length += change.value().len();
+                            }
+                            _ => ranges.push(HighlightRange {
+                                start: text.len(),
+                                length: change.value().len(),
+                                type_id,
+                            }),
+                        }
+                    }
+                    text += change.value();
+                }
+                vec![Self::diff_line(
+                    DiffLineType::Changed,
+                    Some(new_index + 1),
+                    Some(old.offset),
+                    Some(new.offset),
+                    text,
+                    ranges,
+                )]
+            }
+            (Some(old), None) => vec![deleted(old)],
+            (None, Some(new)) => vec![added(new)],
+            (None, None) => vec![],
+        }
+    }
+
+    /// Constructs a diff line.
+    const fn diff_line(
+        line_type: DiffLineType,
+        line_number: Option<usize>,
+        from: Option<usize>,
+        to: Option<usize>,
+        text: String,
+        highlight_ranges: Vec<HighlightRange>,
+    ) -> DiffInfo {
+        DiffInfo {
+            line_number,
+            offset: DiffOffset { from, to },
+            text,
+            type_id: line_type.type_id(),
+            highlight_ranges,
+            move_info: None,
+        }
+    }
+
+    /// Removes unchanged lines that are not within `DIFF_CONTEXT_LINES` of a change.
+    fn remove_distant_context(lines: Vec<DiffInfo>) -> Vec<DiffInfo> {
+        let mut keep = vec![false; lines.len()];
+        for (index, line) in lines.iter().enumerate() {
+            if line.line_type() != DiffLineType::Context {
+                let start = index.saturating_sub(DIFF_CONTEXT_LINES);
+                let end = (index + DIFF_CONTEXT_LINES + 1).min(lines.len());
+                keep[start..end].fill(true);
+            }
+        }
+        lines
+            .into_iter()
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|(line, _)| line)
+            .collect()
+    }
+
+    /// Returns the sections of a wikitext, for a locally computed diff.
+    fn diff_sections(wikitext: &str) -> DiffSections {
+        let sections = WikitextSections::parse(wikitext)
+            .sections()
+            .iter()
+            .map(|section| DiffSection {
+                heading: section.heading.to_owned(),
+                level: section.level,
+                offset: section.offset,
+            })
+            .collect();
+        DiffSections {
+            id: RevisionId::default(),
+            sections,
+            slot_role: "main".to_string(),
+        }
+    }
+
+    /// Splits the diff into hunks of consecutive lines.
+    /// Lines without a line number (deletions) belong to the hunk of the following numbered line.
+    fn hunks(&self) -> Vec<&[DiffInfo]> {
+        let mut ret = vec![];
+        let mut hunk_start = 0;
+        let mut expected_line = None;
+        let mut last_numbered = None;
+        for (index, line) in self.diff.iter().enumerate() {
+            let Some(line_number) = line.line_number else {
+                continue;
+            };
+            if expected_line.is_some_and(|expected| expected != line_number) {
+                let split = last_numbered.map_or(index, |last| last + 1);
+                ret.push(&self.diff[hunk_start..split]);
+                hunk_start = split;
+            }
+            expected_line = Some(line_number + 1);
+            last_numbered = Some(index);
+        }
+        if hunk_start < self.diff.len() {
+            ret.push(&self.diff[hunk_start..]);
+        }
+        ret
+    }
+
+    /// Returns the unified diff hunk header.
+    fn hunk_header(hunk: &[DiffInfo]) -> String {
+        hunk.iter().find_map(|line| line.line_number).map_or_else(
+            || "@@ @@".to_string(),
+            |line_number| format!("@@ +{line_number} @@"),
+        )
+    }
+
+    /// Returns the old text of a changed or deleted line, for unified diffs.
+    fn unified_old_line(line: &DiffInfo) -> Option<String> {
+        match line.line_type() {
+            DiffLineType::Deleted | DiffLineType::MovedFrom | DiffLineType::Changed => {
+                line.old_text()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the prefixed new or context text of a line, for unified diffs.
+    fn unified_new_line(line: &DiffInfo) -> Option<String> {
+        match line.line_type() {
+            DiffLineType::Added | DiffLineType::MovedTo | DiffLineType::Changed => {
+                line.new_text().map(|text| format!("+{text}"))
+            }
+            DiffLineType::Context | DiffLineType::Unknown(_) => Some(format!(" {}", line.text)),
+            DiffLineType::Deleted | DiffLineType::MovedFrom => None,
+        }
+    }
+
+    /// Renders one side of a changed line in ANSI colors, with the changed segments highlighted.
+    fn ansi_changed_line(line: &DiffInfo, side: HighlightType) -> String {
+        let (prefix, color, background) = match side {
+            HighlightType::Deleted => ("-", ANSI_RED, ANSI_RED_BACKGROUND),
+            HighlightType::Added => ("+", ANSI_GREEN, ANSI_GREEN_BACKGROUND),
+        };
+        let mut ret = format!("{color}{prefix}");
+        for segment in line.segments() {
+            match segment.highlight {
+                None => ret += segment.text,
+                Some(highlight) if highlight == side => {
+                    ret += &format!("{background}{}{ANSI_RESET}{color}", segment.text);
+                }
+                Some(_) => {}
+            }
+        }
+        ret += ANSI_RESET;
+        ret.push('\n');
+        ret
+    }
+
+    /// Renders one side of a line as HTML, with the changed segments highlighted.
+    fn html_segments(line: &DiffInfo, side: HighlightType) -> String {
+        let tag = match side {
+            HighlightType::Deleted => "del",
+            HighlightType::Added => "ins",
+        };
+        let mut ret = String::new();
+        for segment in line.segments() {
+            match segment.highlight {
+                None => ret += &html_escape(segment.text),
+                Some(highlight) if highlight == side => {
+                    ret += &format!(
+                        "<{tag} class=\"diffchange diffchange-inline\">{}</{tag}>",
+                        html_escape(segment.text)
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        ret
+    }
+
+    /// Returns a marker cell and a content cell for a side-by-side HTML diff.
+    fn html_cell(marker: &str, class: &str, html: &str) -> String {
+        let marker = if marker.is_empty() {
+            String::new()
+        } else {
+            format!(" data-marker=\"{marker}\"")
+        };
+        format!(
+            "<td class=\"diff-marker\"{marker}></td><td class=\"{class}\"><div>{html}</div></td>"
+        )
+    }
+
+    /// Returns an empty side for a side-by-side HTML diff, the old (deleted) or the new (added) side.
+    fn html_empty_cell(side: HighlightType) -> String {
+        let class = match side {
+            HighlightType::Deleted => "diff-side-deleted",
+            HighlightType::Added => "diff-side-added",
+        };
+        format!("<td colspan=\"2\" class=\"diff-empty {class}\"></td>")
+    }
+}
+
+/// Escapes text for use in HTML.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn test_diff() -> Diff {
+        let text: String =
+            std::fs::read_to_string("test_data/revision_compare.json").expect("Test file missing");
+        let json: Value = serde_json::from_str(&text).expect("Failed to parse JSON");
+        serde_json::from_value(json).expect("Failed to parse diff")
+    }
+
+    fn changed_line() -> DiffInfo {
+        serde_json::from_value(json!({
+            "lineNumber": 7,
+            "offset": { "from": 10, "to": 12 },
+            "text": "The quick brown red fox",
+            "type": 3,
+            "highlightRanges": [
+                { "start": 10, "length": 6, "type": 1 },
+                { "start": 16, "length": 4, "type": 0 }
+            ]
+        }))
+        .expect("Failed to parse diff line")
+    }
+
+    #[test]
+    fn test_line_type() {
+        let diff = test_diff();
+        let types: Vec<_> = diff.diff.iter().map(DiffInfo::line_type).collect();
+        assert_eq!(
+            types,
+            [
+                DiffLineType::Context,
+                DiffLineType::Context,
+                DiffLineType::Added,
+                DiffLineType::Deleted,
+                DiffLineType::Context,
+                DiffLineType::Context
+            ]
+        );
+        assert_eq!(DiffLineType::from(9), DiffLineType::Unknown(9));
+    }
+
+    #[test]
+    fn test_segments() {
+        let line = changed_line();
+        let segments = line.segments();
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[1].text, "brown ");
+        assert_eq!(segments[1].highlight, Some(HighlightType::Deleted));
+        assert_eq!(segments[2].text, "red ");
+        assert_eq!(segments[2].highlight, Some(HighlightType::Added));
+        assert_eq!(line.old_text().as_deref(), Some("The quick brown fox"));
+        assert_eq!(line.new_text().as_deref(), Some("The quick red fox"));
+    }
+
+    #[test]
+    fn test_to_unified() {
+        let unified = test_diff().to_unified();
+        assert!(unified.starts_with("@@ +528 @@\n \n ==== Trait objects ====\n+Generic functions"));
+        assert!(unified.contains("\n-Rust supports two ways"));
+        assert_eq!(unified.lines().count(), 7);
+    }
+
+    #[test]
+    fn test_to_unified_hunks() {
+        let mut diff = test_diff();
+        diff.diff = vec![changed_line(), diff.diff[3].clone(), diff.diff[0].clone()];
+        let unified = diff.to_unified();
+        assert!(unified.starts_with(
+            "@@ +7 @@\n-The quick brown fox\n+The quick red fox\n@@ +528 @@\n-Rust supports"
+        ));
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let mut diff = test_diff();
+        diff.diff.push(changed_line());
+        let ansi = diff.to_ansi();
+        assert!(ansi.contains("\x1b[32m+Generic functions"));
+        assert!(ansi.contains("\x1b[31m-Rust supports"));
+        assert!(ansi.contains("\x1b[31m-The quick \x1b[41;97mbrown \x1b[0m\x1b[31mfox\x1b[0m\n"));
+        assert!(ansi.contains("\x1b[32m+The quick \x1b[42;97mred \x1b[0m\x1b[32mfox\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_from_wikitext() {
+        let from =
+            "Intro\n== A ==\none\ntwo\nThe quick brown fox\nthree\nfour\nfive\nsix\nold line";
+        let to = "Intro\n== A ==\none\ntwo\nThe quick red fox\nthree\nfour\nfive\nsix\nnew text here\n== B ==";
+        let diff = Diff::from_wikitext(from, to);
+        let types: Vec<_> = diff.diff.iter().map(DiffInfo::line_type).collect();
+        assert_eq!(
+            types,
+            [
+                DiffLineType::Context,
+                DiffLineType::Context,
+                DiffLineType::Changed,
+                DiffLineType::Context,
+                DiffLineType::Context,
+                DiffLineType::Context,
+                DiffLineType::Context,
+                DiffLineType::Deleted,
+                DiffLineType::Added,
+                DiffLineType::Added,
+            ]
+        );
+        let changed = &diff.diff[2];
+        assert_eq!(changed.line_number, Some(5));
+        assert_eq!(changed.offset.from, Some(22));
+        assert_eq!(changed.offset.to, Some(22));
+        assert_eq!(changed.old_text().as_deref(), Some("The quick brown fox"));
+        assert_eq!(changed.new_text().as_deref(), Some("The quick red fox"));
+        assert_eq!(diff.diff[7].line_number, None);
+        assert_eq!(diff.diff[7].offset.to, None);
+        assert_eq!(diff.diff[9].text, "== B ==");
+        assert_eq!(diff.from.sections.len(), 1);
+        assert_eq!(diff.to.sections.len(), 2);
+        assert_eq!(diff.to.sections[1].offset, 74);
+    }
+
+    #[test]
+    fn test_from_wikitext_unified() {
+        let diff = Diff::from_wikitext("a\nb\nc\nd\ne\nf\ng\nh", "a\nb\nc\nd\ne\nf\ng\nH");
+        assert_eq!(diff.to_unified(), "@@ +6 @@\n f\n g\n-h\n+H\n");
+        assert!(Diff::from_wikitext("same", "same").diff.is_empty());
+    }
+
+    #[test]
+    fn test_from_wikitext_empty() {
+        let created = Diff::from_wikitext("", "a\nb");
+        let types: Vec<_> = created.diff.iter().map(DiffInfo::line_type).collect();
+        assert_eq!(types, [DiffLineType::Added, DiffLineType::Added]);
+        assert_eq!(created.diff[0].text, "a");
+        assert_eq!(created.diff[1].offset.to, Some(2));
+        assert_eq!(created.to_unified(), "@@ +1 @@\n+a\n+b\n");
+    }
+
+    #[test]
+    fn test_to_side_by_side_html() {
+        let mut diff = test_diff();
+        diff.diff.push(changed_line());
+        let html = diff.to_side_by_side_html();
+        assert!(html.starts_with("<table class=\"diff\">"));
+        assert!(html.contains("Line 528:"));
+        assert!(html.contains("class=\"diff-addedline\"><div>Generic functions"));
+        assert!(html.contains("&lt;code&gt;&amp;dyn Trait&lt;/code&gt;"));
+        assert!(html.contains(
+            "<div>The quick <del class=\"diffchange diffchange-inline\">brown </del>fox</div>"
+        ));
+        assert!(html.contains(
+            "<div>The quick <ins class=\"diffchange diffchange-inline\">red </ins>fox</div>"
+        ));
+
+        // The empty cell of a deleted line is on the new side
+        let deleted = Diff::from_wikitext("a\nb\nc", "a\nc").to_side_by_side_html();
+        assert!(deleted.contains(
+            "<div>b</div></td><td colspan=\"2\" class=\"diff-empty diff-side-added\"></td></tr>"
+        ));
+        assert!(!deleted.contains("diff-side-deleted"));
+    }
+}
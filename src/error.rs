@@ -1,3 +1,4 @@
+use crate::id::RevisionId;
 use reqwest::header::InvalidHeaderValue;
 use serde::Deserialize;
 use serde_json::{Value, json};
@@ -100,6 +101,21 @@ pub enum RestApiError {
     InvalidPrecision,
     #[error("Missing results field in response")]
     MissingResults,
+    #[error("Section not found: {0}")]
+    SectionNotFound(usize),
+    #[error("Revision {revision} is not the latest revision of the page, which is {latest}")]
+    NotLatestRevision {
+        revision: RevisionId,
+        latest: RevisionId,
+    },
+    #[error("Revision {0} has no parent revision")]
+    NoParentRevision(RevisionId),
+    #[error("Revision {revision} does not belong to page {title}")]
+    RevisionNotOnPage { revision: RevisionId, title: String },
+    #[error("A transform for revision {0} requires a title")]
+    TransformTitleRequired(RevisionId),
+    #[error("Invalid timestamp: {0}")]
+    InvalidTimestamp(String),
 }
 
 impl From<reqwest::Error> for RestApiError {
@@ -0,0 +1,810 @@
+use crate::{
+    citation::Citation,
+    plaintext::{Plaintext, PlaintextOptions},
+    table::{Infobox, Table},
+};
+use scraper::{CaseSensitivity, ElementRef, Html, Node, Selector};
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{MapAccess, Visitor},
+    ser::SerializeMap,
+};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fmt, sync::LazyLock};
+
+/// A template parameter value in `data-mw`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateParam {
+    /// The wikitext of the value
+    #[serde(default)]
+    pub wt: String,
+    /// The HTML of the value, if it was edited as HTML
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Template parameters in `data-mw`, in their original order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TemplateParams(pub Vec<(String, TemplateParam)>);
+
+impl TemplateParams {
+    /// Returns the parameter with the given name.
+    pub fn get(&self, name: &str) -> Option<&TemplateParam> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, param)| param)
+    }
+
+    /// Returns the wikitext of the parameter with the given name.
+    pub fn get_wikitext(&self, name: &str) -> Option<&str> {
+        self.get(name).map(|param| param.wt.as_str())
+    }
+
+    /// Returns the parameter names, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(key, _)| key.as_str())
+    }
+
+    /// Sets the wikitext of a parameter, keeping its position, or appends it if it does not exist.
+    pub fn set(&mut self, name: &str, wikitext: &str) {
+        match self.0.iter_mut().find(|(key, _)| key == name) {
+            Some((_, param)) => {
+                param.wt = wikitext.to_string();
+                param.html = None;
+            }
+            None => self.0.push((
+                name.to_string(),
+                TemplateParam {
+                    wt: wikitext.to_string(),
+                    ..Default::default()
+                },
+            )),
+        }
+    }
+
+    /// Renames a parameter, keeping its position and value.
+    /// Returns `false` if there is no parameter `old`, or a parameter `new` already exists.
+    pub fn rename(&mut self, old: &str, new: &str) -> bool {
+        if self.get(new).is_some() {
+            return false;
+        }
+        match self.0.iter_mut().find(|(key, _)| key == old) {
+            Some((key, _)) => {
+                *key = new.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a parameter, and returns it.
+    pub fn remove(&mut self, name: &str) -> Option<TemplateParam> {
+        let pos = self.0.iter().position(|(key, _)| key == name)?;
+        Some(self.0.remove(pos).1)
+    }
+
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Serialize for TemplateParams {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, param) in &self.0 {
+            map.serialize_entry(key, param)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TemplateParams {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ParamsVisitor;
+
+        impl<'de> Visitor<'de> for ParamsVisitor {
+            type Value = TemplateParams;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a map of template parameters")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut ret = vec![];
+                while let Some((key, param)) = access.next_entry()? {
+                    ret.push((key, param));
+                }
+                Ok(TemplateParams(ret))
+            }
+        }
+
+        deserializer.deserialize_map(ParamsVisitor)
+    }
+}
+
+/// The target of a template call or parser function in `data-mw`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateTarget {
+    /// The wikitext of the target, eg `Infobox person` or `#if:`
+    pub wt: String,
+    /// The relative URL of the template page, eg `./Template:Infobox_person`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+    /// The name of the parser function
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// A template call, template argument or parser function call in `data-mw`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateCall {
+    pub target: TemplateTarget,
+    #[serde(default)]
+    pub params: TemplateParams,
+    /// Index of the call within the transclusion
+    #[serde(default)]
+    pub i: usize,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl TemplateCall {
+    /// Returns the title of the template page, eg `Template:Infobox person`,
+    /// or `None` for parser functions.
+    pub fn title(&self) -> Option<String> {
+        let href = self.target.href.as_deref()?;
+        Some(href_to_title(href).0)
+    }
+
+    /// Returns the template name as written in the wikitext, trimmed.
+    pub fn name(&self) -> &str {
+        self.target.wt.trim()
+    }
+
+    /// Checks if this calls the template with the given name, with or without namespace prefix.
+    /// First letters and underscores are normalized.
+    pub fn is_template(&self, name: &str) -> bool {
+        let Some(title) = self.title() else {
+            return false;
+        };
+        let name = normalize_title(name);
+        let title = normalize_title(&title);
+        title == name
+            || title
+                .split_once(':')
+                .is_some_and(|(_, title)| normalize_title(title) == name)
+    }
+}
+
+/// A part of a transclusion in `data-mw`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransclusionPart {
+    /// Literal wikitext between template calls
+    Wikitext(String),
+    Template {
+        template: TemplateCall,
+    },
+    TemplateArg {
+        templatearg: TemplateCall,
+    },
+    ParserFunction {
+        parserfunction: TemplateCall,
+    },
+    Other(Value),
+}
+
+/// The `data-mw` of a transclusion.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransclusionData {
+    pub parts: Vec<TransclusionPart>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// A transclusion (`typeof="mw:Transclusion"`) of one or more templates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transclusion {
+    /// The `about` ID, shared by all elements generated by the transclusion
+    pub about: Option<String>,
+    /// The element ID
+    pub id: Option<String>,
+    pub data: TransclusionData,
+}
+
+impl Transclusion {
+    /// Returns the template calls of the transclusion.
+    pub fn templates(&self) -> impl Iterator<Item = &TemplateCall> {
+        self.data.parts.iter().filter_map(|part| match part {
+            TransclusionPart::Template { template } => Some(template),
+            _ => None,
+        })
+    }
+
+    /// Returns the first call of the template with the given name.
+    pub fn template(&self, name: &str) -> Option<&TemplateCall> {
+        self.templates().find(|template| template.is_template(name))
+    }
+}
+
+/// An internal link (`rel="mw:WikiLink"`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WikiLink {
+    pub href: String,
+    /// The target page title, eg `Programming paradigm`
+    pub title: String,
+    pub fragment: Option<String>,
+    pub text: String,
+}
+
+/// An external link (`rel="mw:ExtLink"`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalLink {
+    pub href: String,
+    pub text: String,
+}
+
+/// A reference (`typeof="mw:Extension/ref"`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+    /// The element ID of the reference marker
+    pub id: Option<String>,
+    /// The name of a named reference
+    pub name: Option<String>,
+    pub group: Option<String>,
+    /// The text of the reference body
+    pub text: String,
+    /// The HTML of the reference body
+    pub html: String,
+}
+
+/// A media item (`typeof="mw:File"`, `mw:File/Thumb` etc).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Media {
+    /// The media type, eg `mw:File/Thumb`
+    pub media_type: String,
+    /// The file page title, eg `File:Example.jpg`
+    pub file: Option<String>,
+    pub src: Option<String>,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub alt: Option<String>,
+    pub caption: Option<String>,
+}
+
+/// A section (`<section data-mw-section-id>`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HtmlSection {
+    /// The section ID; 0 is the lead section, negative IDs are pseudo-sections
+    pub id: i64,
+    /// The heading level (2 for `==`), or `None` for the lead section
+    pub level: Option<usize>,
+    pub heading: Option<String>,
+    /// The heading anchor
+    pub anchor: Option<String>,
+}
+
+/// A parsed Parsoid HTML document, as returned by `Page::get_html`, `Revision::get_html`
+/// and `Transform::wikitext2html`, with typed views on its wiki elements.
+#[derive(Clone, Debug)]
+pub struct ParsoidDocument {
+    document: Html,
+}
+
+impl ParsoidDocument {
+    /// Parses a Parsoid HTML document.
+    pub fn parse(html: &str) -> Self {
+        Self {
+            document: Html::parse_document(html),
+        }
+    }
+
+    /// Returns the underlying DOM, for custom queries.
+    pub const fn document(&self) -> &Html {
+        &self.document
+    }
+
+    /// Returns the underlying DOM mutably.
+    pub const fn document_mut(&mut self) -> &mut Html {
+        &mut self.document
+    }
+
+    /// Serializes the document to HTML.
+    pub fn html(&self) -> String {
+        self.document.html()
+    }
+
+    /// Returns all transclusions with template data.
+    pub fn transclusions(&self) -> Vec<Transclusion> {
+        self.document
+            .select(&TRANSCLUSION_SELECTOR)
+            .filter_map(|element| {
+                let data = serde_json::from_str(element.attr("data-mw")?).ok()?;
+                Some(Transclusion {
+                    about: element.attr("about").map(str::to_string),
+                    id: element.attr("id").map(str::to_string),
+                    data,
+                })
+            })
+            .collect()
+    }
+
+    /// Calls `f` for every call of the template `name` (see `TemplateCall::is_template`),
+    /// which returns `true` if it changed the call.
+    /// The `data-mw` of changed transclusions is updated in the document, so the document can be saved
+    /// with `Page::edit_html`. Returns the number of changed template calls.
+    pub fn edit_templates<F>(&mut self, name: &str, mut f: F) -> usize
+    where
+        F: FnMut(&mut TemplateCall) -> bool,
+    {
+        let transclusions: Vec<_> = self
+            .document
+            .select(&TRANSCLUSION_SELECTOR)
+            .filter_map(|element| {
+                let data: TransclusionData = serde_json::from_str(element.attr("data-mw")?).ok()?;
+                Some((element.id(), data))
+            })
+            .collect();
+        let mut ret = 0;
+        for (node_id, mut data) in transclusions {
+            let mut changed = 0;
+            for part in &mut data.parts {
+                if let TransclusionPart::Template { template } = part
+                    && template.is_template(name)
+                    && f(template)
+                {
+                    changed += 1;
+                }
+            }
+            if changed == 0 {
+                continue;
+            }
+            let Ok(data_mw) = serde_json::to_string(&data) else {
+                continue;
+            };
+            if let Some(mut node) = self.document.tree.get_mut(node_id)
+                && let Node::Element(element) = node.value()
+                && let Some((_, value)) = element
+                    .attrs
+                    .iter_mut()
+                    .find(|(attr, _)| &*attr.local == "data-mw")
+            {
+                *value = data_mw.as_str().into();
+                ret += changed;
+            }
+        }
+        ret
+    }
+
+    /// Renames the parameter `old` to `new` in all calls of the template `name`,
+    /// and returns the number of changed calls.
+    pub fn rename_template_param(&mut self, name: &str, old: &str, new: &str) -> usize {
+        self.edit_templates(name, |template| template.params.rename(old, new))
+    }
+
+    /// Returns all internal links.
+    pub fn wiki_links(&self) -> Vec<WikiLink> {
+        self.document
+            .select(&WIKI_LINK_SELECTOR)
+            .filter_map(|element| {
+                let href = element.attr("href")?.to_string();
+                let (title, fragment) = href_to_title(&href);
+                Some(WikiLink {
+                    href,
+                    title,
+                    fragment,
+                    text: element_text(&element),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns all external links.
+    pub fn external_links(&self) -> Vec<ExternalLink> {
+        self.document
+            .select(&EXT_LINK_SELECTOR)
+            .filter_map(|element| {
+                Some(ExternalLink {
+                    href: element.attr("href")?.to_string(),
+                    text: element_text(&element),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns all references, with their bodies.
+    pub fn references(&self) -> Vec<Reference> {
+        let bodies: HashMap<&str, ElementRef> = self
+            .document
+            .select(&REFERENCE_TEXT_SELECTOR)
+            .filter_map(|element| Some((element.attr("id")?, element)))
+            .collect();
+        self.document
+            .select(&REFERENCE_SELECTOR)
+            .map(|element| {
+                let data_mw: Value = element
+                    .attr("data-mw")
+                    .and_then(|data_mw| serde_json::from_str(data_mw).ok())
+                    .unwrap_or_default();
+                let attr = |key: &str| data_mw["attrs"][key].as_str().map(str::to_string);
+                let body = data_mw["body"]["id"].as_str().and_then(|id| bodies.get(id));
+                let (text, html) = body.map_or_else(
+                    || {
+                        let html = data_mw["body"]["html"].as_str().unwrap_or_default();
+                        let fragment = Html::parse_fragment(html);
+                        (element_text(&fragment.root_element()), html.to_string())
+                    },
+                    |body| (element_text(body), body.inner_html()),
+                );
+                Reference {
+                    id: element.attr("id").map(str::to_string),
+                    name: attr("name"),
+                    group: attr("group"),
+                    text,
+                    html,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns all references as citations, with the citation templates from their `data-mw`,
+    /// and DOIs, ISBNs and URLs. Reused named references get the content of the reference that defines them.
+    pub fn citations(&self) -> Vec<Citation> {
+        let mut ret: Vec<Citation> = self
+            .references()
+            .iter()
+            .map(Citation::from_reference)
+            .collect();
+        Citation::fill_reused(&mut ret);
+        ret
+    }
+
+    /// Returns all media items.
+    pub fn media(&self) -> Vec<Media> {
+        self.document
+            .select(&FILE_SELECTOR)
+            .filter_map(|element| {
+                let media_type = element
+                    .attr("typeof")?
+                    .split_whitespace()
+                    .find(|t| t.starts_with("mw:File"))?
+                    .to_string();
+                let inner = element.select(&RESOURCE_SELECTOR).next();
+                let inner_attr = |key: &str| inner.and_then(|inner| inner.attr(key));
+                let caption = element
+                    .select(&FIGCAPTION_SELECTOR)
+                    .next()
+                    .map(|caption| element_text(&caption))
+                    .filter(|caption| !caption.is_empty());
+                Some(Media {
+                    media_type,
+                    file: inner_attr("resource").map(|resource| href_to_title(resource).0),
+                    src: inner_attr("src").map(str::to_string),
+                    width: inner_attr("width").and_then(|w| w.parse().ok()),
+                    height: inner_attr("height").and_then(|h| h.parse().ok()),
+                    alt: inner_attr("alt").map(str::to_string),
+                    caption,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns all infoboxes (tables with the `infobox` class), with the template that generated them.
+    pub fn infoboxes(&self) -> Vec<Infobox> {
+        let transclusions = self.transclusions();
+        self.document
+            .select(&INFOBOX_SELECTOR)
+            .map(|table| {
+                let template = table.attr("about").and_then(|about| {
+                    transclusions
+                        .iter()
+                        .find(|transclusion| transclusion.about.as_deref() == Some(about))
+                        .and_then(|transclusion| transclusion.templates().next())
+                        .map(|template| template.name().to_string())
+                });
+                Infobox::from_element(&table, template)
+            })
+            .collect()
+    }
+
+    /// Returns all tables with the `wikitable` class, as grids.
+    pub fn tables(&self) -> Vec<Table> {
+        self.document
+            .select(&WIKITABLE_SELECTOR)
+            .map(|table| Table::from_element(&table))
+            .collect()
+    }
+
+    /// Returns the prose of the document by section, without the elements and sections removed by `options`.
+    pub fn plaintext(&self, options: &PlaintextOptions) -> Plaintext {
+        Plaintext::from_html(&self.document, options)
+    }
+
+    /// Returns all sections, in document order.
+    pub fn sections(&self) -> Vec<HtmlSection> {
+        self.document
+            .select(&SECTION_SELECTOR)
+            .filter_map(|element| {
+                let id = element.attr("data-mw-section-id")?.parse().ok()?;
+                let heading = element
+                    .child_elements()
+                    .next()
+                    .filter(|child| HEADING_SELECTOR.matches(child));
+                Some(HtmlSection {
+                    id,
+                    level: heading.and_then(|h| h.value().name()[1..].parse().ok()),
+                    heading: heading.map(|h| element_text(&h)),
+                    anchor: heading.and_then(|h| h.attr("id")).map(str::to_string),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Declares CSS selectors that are parsed once, on first use.
+/// The selectors are constant, and checked by `test_selectors`.
+macro_rules! selectors {
+    ($($name:ident = $css:literal;)*) => {
+        $(
+            pub(crate) static $name: LazyLock<Selector> =
+                LazyLock::new(|| Selector::parse($css).expect("Invalid CSS selector"));
+        )*
+
+        #[cfg(test)]
+        const SELECTORS: &[&LazyLock<Selector>] = &[$(&$name),*];
+    };
+}
+
+selectors! {
+    TRANSCLUSION_SELECTOR = "[typeof~=\"mw:Transclusion\"][data-mw]";
+    WIKI_LINK_SELECTOR = "a[rel~=\"mw:WikiLink\"][href]";
+    EXT_LINK_SELECTOR = "a[rel~=\"mw:ExtLink\"][href]";
+    REFERENCE_TEXT_SELECTOR = ".mw-reference-text[id]";
+    REFERENCE_SELECTOR = "[typeof~=\"mw:Extension/ref\"]";
+    FILE_SELECTOR = "[typeof*=\"mw:File\"]";
+    RESOURCE_SELECTOR = "[resource]";
+    FIGCAPTION_SELECTOR = "figcaption";
+    INFOBOX_SELECTOR = "table.infobox";
+    WIKITABLE_SELECTOR = "table.wikitable";
+    HEADING_SELECTOR = "h1, h2, h3, h4, h5, h6";
+    SECTION_SELECTOR = "section[data-mw-section-id]";
+    ROW_SELECTOR = "tr";
+}
+
+/// Returns the text content of an element, trimmed.
+pub(crate) fn element_text(element: &ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
+
+/// Returns the visible text of an element, without styles, scripts and reference markers.
+/// Line breaks and block elements start a new line, other whitespace is collapsed.
+pub(crate) fn visible_text(element: &ElementRef) -> String {
+    fn collect(element: &ElementRef, out: &mut String) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(el) => {
+                    if matches!(el.name(), "style" | "script" | "link" | "meta")
+                        || el.has_class("mw-ref", CaseSensitivity::CaseSensitive)
+                        || el.has_class("reference", CaseSensitivity::CaseSensitive)
+                    {
+                        continue;
+                    }
+                    let block = matches!(
+                        el.name(),
+                        "br" | "p" | "div" | "li" | "ul" | "ol" | "dl" | "dd" | "dt" | "tr"
+                    );
+                    if block {
+                        out.push('\n');
+                    }
+                    if let Some(child) = ElementRef::wrap(child) {
+                        collect(&child, out);
+                    }
+                    if block {
+                        out.push('\n');
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut text = String::new();
+    collect(element, &mut text);
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Converts a Parsoid link like `./Foo_bar#baz` into a page title and fragment.
+fn href_to_title(href: &str) -> (String, Option<String>) {
+    let href = href.strip_prefix("./").unwrap_or(href);
+    let (path, fragment) = match href.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment.to_string())),
+        None => (href, None),
+    };
+    let path = path.split_once('?').map_or(path, |(path, _)| path);
+    let title = urlencoding::decode(path).map_or_else(|_| path.to_string(), |t| t.into_owned());
+    (title.replace('_', " "), fragment)
+}
+
+/// Normalizes a page title for comparison: underscores and first letter.
+fn normalize_title(title: &str) -> String {
+    let title = title.trim().replace('_', " ");
+    let mut chars = title.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_document() -> ParsoidDocument {
+        let html =
+            std::fs::read_to_string("test_data/page_get_html.html").expect("Test file missing");
+        ParsoidDocument::parse(&html)
+    }
+
+    #[test]
+    fn test_selectors() {
+        for selector in SELECTORS {
+            LazyLock::force(selector);
+        }
+    }
+
+    #[test]
+    fn test_transclusions() {
+        let document = test_document();
+        let transclusions = document.transclusions();
+        assert!(!transclusions.is_empty());
+        let short_description = transclusions[0]
+            .template("short description")
+            .expect("Template missing");
+        assert_eq!(short_description.name(), "Short description");
+        assert_eq!(
+            short_description.title().as_deref(),
+            Some("Template:Short description")
+        );
+        assert_eq!(
+            short_description.params.get_wikitext("1"),
+            Some("General-purpose programming language")
+        );
+        assert!(short_description.is_template("Template:Short_description"));
+        assert!(!short_description.is_template("Infobox"));
+    }
+
+    #[test]
+    fn test_template_params_order() {
+        let data: TransclusionData = serde_json::from_str(
+            r#"{"parts":["foo",{"template":{"target":{"wt":"X","href":"./Template:X"},"params":{"z":{"wt":"1"},"a":{"wt":"2"}},"i":0}}]}"#,
+        )
+        .expect("Failed to parse data-mw");
+        let TransclusionPart::Template { template } = &data.parts[1] else {
+            panic!("Template expected");
+        };
+        assert_eq!(template.params.names().collect::<Vec<_>>(), ["z", "a"]);
+        assert_eq!(
+            serde_json::to_string(&data).expect("Failed to serialize"),
+            r#"{"parts":["foo",{"template":{"target":{"wt":"X","href":"./Template:X"},"params":{"z":{"wt":"1"},"a":{"wt":"2"}},"i":0}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_edit_templates() {
+        let html = r##"<p about="#mwt1" typeof="mw:Transclusion" data-mw='{"parts":[{"template":{"target":{"wt":"Cite web ","href":"./Template:Cite_web"},"params":{"url":{"wt":"https://example.org"},"accessdate":{"wt":"2020"},"title":{"wt":"Foo"}},"i":0}}]}' id="mwAg">Foo</p><p about="#mwt2" typeof="mw:Transclusion" data-mw='{"parts":[{"template":{"target":{"wt":"Other","href":"./Template:Other"},"params":{"accessdate":{"wt":"2021"}},"i":0}}]}'>Bar</p>"##;
+        let mut document = ParsoidDocument::parse(html);
+        assert_eq!(
+            document.rename_template_param("cite_web", "accessdate", "access-date"),
+            1
+        );
+        // Nothing left to rename
+        assert_eq!(
+            document.rename_template_param("Cite web", "accessdate", "access-date"),
+            0
+        );
+        let transclusions = document.transclusions();
+        let cite = transclusions[0]
+            .template("Cite web")
+            .expect("Template missing");
+        assert_eq!(
+            cite.params.names().collect::<Vec<_>>(),
+            ["url", "access-date", "title"]
+        );
+        let other = transclusions[1]
+            .template("Other")
+            .expect("Template missing");
+        assert_eq!(other.params.get_wikitext("accessdate"), Some("2021"));
+
+        let changed = document.edit_templates("Cite web", |template| {
+            template.params.set("title", "Bar");
+            template.params.set("language", "en");
+            template.params.remove("url").is_some()
+        });
+        assert_eq!(changed, 1);
+        let edited = document.transclusions();
+        let edited_cite = edited[0].template("Cite web").expect("Template missing");
+        assert_eq!(
+            edited_cite.params.names().collect::<Vec<_>>(),
+            ["access-date", "title", "language"]
+        );
+        assert_eq!(edited_cite.params.get_wikitext("title"), Some("Bar"));
+        assert!(document.html().contains("access-date"));
+    }
+
+    #[test]
+    fn test_links() {
+        let document = test_document();
+        let wiki_links = document.wiki_links();
+        assert_eq!(wiki_links.len(), 788);
+        let paradigm = wiki_links
+            .iter()
+            .find(|link| link.href == "./Programming_paradigm")
+            .expect("Link missing");
+        assert_eq!(paradigm.title, "Programming paradigm");
+        assert_eq!(paradigm.text, "Paradigms");
+        let external_links = document.external_links();
+        assert_eq!(external_links.len(), 285);
+        assert!(
+            external_links
+                .iter()
+                .any(|link| link.href == "https://www.rust-lang.org/"
+                    && link.text == "rust-lang.org")
+        );
+    }
+
+    #[test]
+    fn test_references() {
+        let references = test_document().references();
+        assert_eq!(references.len(), 273);
+        let first = &references[0];
+        assert_eq!(
+            first.name.as_deref(),
+            Some("wikidata-625dfa02256b12affe5cdb18bbe05fea7b2cb7a3-v20")
+        );
+        assert!(!first.text.is_empty());
+        assert!(
+            references
+                .iter()
+                .any(|r| r.group.as_deref() == Some("note"))
+        );
+    }
+
+    #[test]
+    fn test_media() {
+        let media = test_document().media();
+        assert_eq!(media.len(), 23);
+        let thumb = media
+            .iter()
+            .find(|m| m.media_type == "mw:File/Thumb")
+            .expect("Thumbnail missing");
+        assert_eq!(
+            thumb.file.as_deref(),
+            Some("File:MozillaCaliforniaHeadquarters.JPG")
+        );
+        assert_eq!(thumb.width, Some(250));
+        assert!(thumb.caption.is_some());
+    }
+
+    #[test]
+    fn test_sections() {
+        let sections = test_document().sections();
+        assert_eq!(sections[0].id, 0);
+        assert_eq!(sections[0].heading, None);
+        assert_eq!(sections[1].level, Some(2));
+        assert_eq!(sections[1].heading.as_deref(), Some("History"));
+        assert_eq!(sections[1].anchor.as_deref(), Some("History"));
+        assert_eq!(sections[2].level, Some(3));
+    }
+}
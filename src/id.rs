@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Defines a numeric ID newtype, so IDs of different kinds can not be mixed up.
+macro_rules! numeric_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+        )]
+        #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+        #[serde(transparent)]
+        pub struct $name(pub usize);
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl From<usize> for $name {
+            fn from(id: usize) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for usize {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+    };
+}
+
+numeric_id!(
+    /// The ID of a page.
+    PageId
+);
+
+numeric_id!(
+    /// The ID of a revision. Revision IDs are unique across all pages of a wiki.
+    RevisionId
+);
+
+numeric_id!(
+    /// The ID of a registered user.
+    UserId
+);
+
+numeric_id!(
+    /// The numeric ID of a Wikidata item, eg `42` for Q42.
+    ItemId
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialization() {
+        let id: RevisionId = serde_json::from_str("1316925953").expect("Failed to deserialize");
+        assert_eq!(id, RevisionId(1316925953));
+        assert_eq!(id.to_string(), "1316925953");
+        assert_eq!(
+            serde_json::to_string(&PageId(29414838)).expect("Failed to serialize"),
+            "29414838"
+        );
+        assert_eq!(usize::from(ItemId(42)), 42);
+    }
+}
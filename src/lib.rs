@@ -45,14 +45,33 @@
 //! It provides a set of types and methods for interacting with [the API](https://www.mediawiki.org/wiki/API:REST_API).
 
 pub mod bearer_token;
+pub mod blame;
+pub mod bulk;
+pub mod citation;
+pub mod diff;
 pub mod error;
 pub mod file;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod id;
+pub mod lint;
+pub mod lint_fix;
+pub mod lint_report;
 pub mod math;
 pub mod page;
+#[cfg(feature = "html")]
+pub mod plaintext;
 pub mod prelude;
 pub mod rest_api;
 pub mod rest_api_builder;
+pub mod revert;
 pub mod revision;
 pub mod search;
+pub mod section;
+#[cfg(feature = "html")]
+pub mod table;
+pub mod timestamp;
 pub mod transform;
 pub mod utilities;
+pub mod wikitext;
+pub mod wikitext_edit;
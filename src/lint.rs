@@ -0,0 +1,413 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// A Linter category, as used in the `type` field of a `Lint`.
+/// Unknown categories are kept as `Other`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(from = "String", into = "String")]
+pub enum LintCategory {
+    BogusImageOptions,
+    DeletableTableTag,
+    DuplicateIds,
+    EmptyHeading,
+    Fostered,
+    FosteredTransparent,
+    Html5Misnesting,
+    InlineMediaCaption,
+    LargeTables,
+    MiscTidyReplacementIssues,
+    MisnestedTag,
+    MissingEndTag,
+    MissingEndTagInHeading,
+    MissingImageAltText,
+    MultiColonEscape,
+    MultilineHtmlTableInList,
+    MultipleUnclosedFormattingTags,
+    NightModeUnawareBackgroundColor,
+    ObsoleteTag,
+    PwrapBugWorkaround,
+    SelfClosedTag,
+    StrippedTag,
+    TidyFontBug,
+    TidyWhitespaceBug,
+    UnclosedQuotesInHeading,
+    WikilinkInExtlink,
+    Other(String),
+}
+
+impl LintCategory {
+    /// Returns the category name, as used by the Linter extension.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::BogusImageOptions => "bogus-image-options",
+            Self::DeletableTableTag => "deletable-table-tag",
+            Self::DuplicateIds => "duplicate-ids",
+            Self::EmptyHeading => "empty-heading",
+            Self::Fostered => "fostered",
+            Self::FosteredTransparent => "fostered-transparent",
+            Self::Html5Misnesting => "html5-misnesting",
+            Self::InlineMediaCaption => "inline-media-caption",
+            Self::LargeTables => "large-tables",
+            Self::MiscTidyReplacementIssues => "misc-tidy-replacement-issues",
+            Self::MisnestedTag => "misnested-tag",
+            Self::MissingEndTag => "missing-end-tag",
+            Self::MissingEndTagInHeading => "missing-end-tag-in-heading",
+            Self::MissingImageAltText => "missing-image-alt-text",
+            Self::MultiColonEscape => "multi-colon-escape",
+            Self::MultilineHtmlTableInList => "multiline-html-table-in-list",
+            Self::MultipleUnclosedFormattingTags => "multiple-unclosed-formatting-tags",
+            Self::NightModeUnawareBackgroundColor => "night-mode-unaware-background-color",
+            Self::ObsoleteTag => "obsolete-tag",
+            Self::PwrapBugWorkaround => "pwrap-bug-workaround",
+            Self::SelfClosedTag => "self-closed-tag",
+            Self::StrippedTag => "stripped-tag",
+            Self::TidyFontBug => "tidy-font-bug",
+            Self::TidyWhitespaceBug => "tidy-whitespace-bug",
+            Self::UnclosedQuotesInHeading => "unclosed-quotes-in-heading",
+            Self::WikilinkInExtlink => "wikilink-in-extlink",
+            Self::Other(name) => name,
+        }
+    }
+
+    /// Returns the priority of the category, as configured in the Linter extension,
+    /// or `None` for categories unknown to this crate.
+    pub const fn severity(&self) -> Option<LintSeverity> {
+        match self {
+            Self::DeletableTableTag
+            | Self::Html5Misnesting
+            | Self::InlineMediaCaption
+            | Self::MiscTidyReplacementIssues
+            | Self::MissingEndTagInHeading
+            | Self::MultipleUnclosedFormattingTags
+            | Self::PwrapBugWorkaround
+            | Self::SelfClosedTag
+            | Self::TidyFontBug
+            | Self::TidyWhitespaceBug
+            | Self::UnclosedQuotesInHeading => Some(LintSeverity::High),
+            Self::BogusImageOptions
+            | Self::DuplicateIds
+            | Self::Fostered
+            | Self::MisnestedTag
+            | Self::MultiColonEscape
+            | Self::MultilineHtmlTableInList
+            | Self::WikilinkInExtlink => Some(LintSeverity::Medium),
+            Self::EmptyHeading
+            | Self::FosteredTransparent
+            | Self::LargeTables
+            | Self::MissingEndTag
+            | Self::MissingImageAltText
+            | Self::NightModeUnawareBackgroundColor
+            | Self::ObsoleteTag
+            | Self::StrippedTag => Some(LintSeverity::Low),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl From<&str> for LintCategory {
+    fn from(name: &str) -> Self {
+        match name {
+            "bogus-image-options" => Self::BogusImageOptions,
+            "deletable-table-tag" => Self::DeletableTableTag,
+            "duplicate-ids" => Self::DuplicateIds,
+            "empty-heading" => Self::EmptyHeading,
+            "fostered" => Self::Fostered,
+            "fostered-transparent" => Self::FosteredTransparent,
+            "html5-misnesting" => Self::Html5Misnesting,
+            "inline-media-caption" => Self::InlineMediaCaption,
+            "large-tables" => Self::LargeTables,
+            "misc-tidy-replacement-issues" => Self::MiscTidyReplacementIssues,
+            "misnested-tag" => Self::MisnestedTag,
+            "missing-end-tag" => Self::MissingEndTag,
+            "missing-end-tag-in-heading" => Self::MissingEndTagInHeading,
+            "missing-image-alt-text" => Self::MissingImageAltText,
+            "multi-colon-escape" => Self::MultiColonEscape,
+            "multiline-html-table-in-list" => Self::MultilineHtmlTableInList,
+            "multiple-unclosed-formatting-tags" => Self::MultipleUnclosedFormattingTags,
+            "night-mode-unaware-background-color" => Self::NightModeUnawareBackgroundColor,
+            "obsolete-tag" => Self::ObsoleteTag,
+            "pwrap-bug-workaround" => Self::PwrapBugWorkaround,
+            "self-closed-tag" => Self::SelfClosedTag,
+            "stripped-tag" => Self::StrippedTag,
+            "tidy-font-bug" => Self::TidyFontBug,
+            "tidy-whitespace-bug" => Self::TidyWhitespaceBug,
+            "unclosed-quotes-in-heading" => Self::UnclosedQuotesInHeading,
+            "wikilink-in-extlink" => Self::WikilinkInExtlink,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for LintCategory {
+    fn from(name: String) -> Self {
+        Self::from(name.as_str())
+    }
+}
+
+impl From<LintCategory> for String {
+    fn from(category: LintCategory) -> Self {
+        category.as_str().to_string()
+    }
+}
+
+impl fmt::Display for LintCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl PartialEq<&str> for LintCategory {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+/// The priority of a Linter category, as shown on `Special:LintErrors`.
+/// Variants are ordered from high to low priority.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    High,
+    Medium,
+    Low,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::High => write!(f, "high"),
+            Self::Medium => write!(f, "medium"),
+            Self::Low => write!(f, "low"),
+        }
+    }
+}
+
+/// The parameters of a `Lint`. Common parameters are typed, all others are kept in `other`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LintParams {
+    /// The tag name, eg `center` for `obsolete-tag` or `table` for `missing-end-tag`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The duplicated ID, for `duplicate-ids`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The bogus options, for `bogus-image-options`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<String>>,
+    /// The link target, for `multi-colon-escape`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+    #[serde(
+        rename = "inTable",
+        alias = "in_table",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub in_table: Option<bool>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// A source range in the wikitext, based on the Parsoid DSR (DOM source range) of a `Lint`.
+/// All offsets are in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourceRange {
+    pub start: usize,
+    pub end: usize,
+    /// Width of the opening tag, if known
+    pub open_width: Option<usize>,
+    /// Width of the closing tag, if known
+    pub close_width: Option<usize>,
+}
+
+impl SourceRange {
+    /// Returns the byte range of the content between the opening and closing tags.
+    pub fn inner(&self) -> std::ops::Range<usize> {
+        let start = self.start + self.open_width.unwrap_or(0);
+        let end = self.end.saturating_sub(self.close_width.unwrap_or(0));
+        start..end.max(start)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TemplateInfo {
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Lint {
+    #[serde(rename = "type", alias = "type_name")]
+    pub type_name: LintCategory,
+    pub dsr: Vec<Option<usize>>,
+    #[serde(rename = "templateInfo", alias = "template_info")]
+    pub template_info: Option<TemplateInfo>,
+    #[serde(default, deserialize_with = "deserialize_params")]
+    pub params: LintParams,
+}
+
+impl Lint {
+    /// Returns the category of the lint.
+    pub const fn category(&self) -> &LintCategory {
+        &self.type_name
+    }
+
+    /// Returns the priority of the lint category, see `LintCategory::severity`.
+    pub const fn severity(&self) -> Option<LintSeverity> {
+        self.type_name.severity()
+    }
+
+    /// Returns the source range of the lint, or `None` if the start or end offset is unknown.
+    pub fn source_range(&self) -> Option<SourceRange> {
+        let dsr = |index: usize| self.dsr.get(index).copied().flatten();
+        Some(SourceRange {
+            start: dsr(0)?,
+            end: dsr(1)?,
+            open_width: dsr(2),
+            close_width: dsr(3),
+        })
+    }
+
+    /// Returns the offending wikitext snippet, given the wikitext the lint was generated for,
+    /// eg the source returned by `Page::get`.
+    pub fn snippet<'a>(&self, wikitext: &'a str) -> Option<&'a str> {
+        let range = self.source_range()?;
+        wikitext.get(range.start..range.end)
+    }
+}
+
+/// Deserializes lint parameters; `MediaWiki` sends an empty array instead of an empty object.
+/// An empty array or null is read as no parameters, any other non-object value is an error.
+fn deserialize_params<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LintParams, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Object(map) => {
+            serde_json::from_value(Value::Object(map)).map_err(serde::de::Error::custom)
+        }
+        Value::Null => Ok(LintParams::default()),
+        Value::Array(array) if array.is_empty() => Ok(LintParams::default()),
+        other => Err(serde::de::Error::invalid_type(
+            serde::de::Unexpected::Other(&other.to_string()),
+            &"an object, an empty array or null",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_json(test_file: &str) -> Value {
+        let test_text: String =
+            std::fs::read_to_string(format!("test_data/{test_file}")).expect("Test file missing");
+        serde_json::from_str(&test_text).expect("Failed to parse JSON")
+    }
+
+    #[test]
+    fn test_category() {
+        assert_eq!(
+            LintCategory::from("obsolete-tag"),
+            LintCategory::ObsoleteTag
+        );
+        assert_eq!(LintCategory::ObsoleteTag, "obsolete-tag");
+        assert_eq!(
+            LintCategory::from("some-new-lint"),
+            LintCategory::Other("some-new-lint".to_string())
+        );
+        assert_eq!(
+            LintCategory::from("some-new-lint").to_string(),
+            "some-new-lint"
+        );
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(
+            LintCategory::SelfClosedTag.severity(),
+            Some(LintSeverity::High)
+        );
+        assert_eq!(
+            LintCategory::MisnestedTag.severity(),
+            Some(LintSeverity::Medium)
+        );
+        assert_eq!(
+            LintCategory::ObsoleteTag.severity(),
+            Some(LintSeverity::Low)
+        );
+        assert_eq!(LintCategory::from("some-new-lint").severity(), None);
+        assert!(LintSeverity::High < LintSeverity::Low);
+        assert_eq!(
+            serde_json::to_value(LintSeverity::Medium).expect("Failed to serialize"),
+            "medium"
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let lints: Vec<Lint> =
+            serde_json::from_value(read_json("wikitext2lint.json")).expect("Failed to parse lints");
+        assert_eq!(lints[0].category(), &LintCategory::MissingEndTag);
+        assert_eq!(lints[0].params.name.as_deref(), Some("table"));
+        assert_eq!(lints[0].params.in_table, Some(true));
+        assert_eq!(lints[1].category(), &LintCategory::DeletableTableTag);
+        let lint: Lint = serde_json::from_value(serde_json::json!({
+            "type": "bogus-image-options",
+            "dsr": [0, 10, null, null],
+            "params": { "items": ["foo"], "ext": "bar" }
+        }))
+        .expect("Failed to parse lint");
+        assert_eq!(lint.params.items, Some(vec!["foo".to_string()]));
+        assert_eq!(lint.params.other["ext"], "bar");
+        let empty: Lint = serde_json::from_value(serde_json::json!({
+            "type": "fostered",
+            "dsr": [0, 10, null, null],
+            "params": []
+        }))
+        .expect("Failed to parse lint");
+        assert_eq!(empty.params, LintParams::default());
+        let null: Lint = serde_json::from_value(serde_json::json!({
+            "type": "fostered",
+            "dsr": [0, 10, null, null],
+            "params": null
+        }))
+        .expect("Failed to parse lint");
+        assert_eq!(null.params, LintParams::default());
+        for invalid in [serde_json::json!(["foo"]), serde_json::json!("foo")] {
+            assert!(
+                serde_json::from_value::<Lint>(serde_json::json!({
+                    "type": "fostered",
+                    "dsr": [0, 10, null, null],
+                    "params": invalid
+                }))
+                .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_source_range() {
+        let lints: Vec<Lint> =
+            serde_json::from_value(read_json("wikitext2lint.json")).expect("Failed to parse lints");
+        let range = lints[0].source_range().expect("Source range missing");
+        assert_eq!(range.start, 39);
+        assert_eq!(range.end, 46);
+        assert_eq!(range.open_width, Some(7));
+        assert_eq!(range.inner(), 46..46);
+    }
+
+    #[test]
+    fn test_snippet() {
+        let revision = read_json("revision_get.json");
+        let wikitext = revision["source"].as_str().expect("Source missing");
+        let lints: Vec<Lint> =
+            serde_json::from_value(read_json("revision_lint.json")).expect("Failed to parse lints");
+        for lint in &lints {
+            let snippet = lint.snippet(wikitext).expect("Snippet missing");
+            assert!(snippet.to_lowercase().starts_with("{{cite"));
+            assert!(snippet.ends_with("}}"));
+        }
+    }
+}
@@ -0,0 +1,657 @@
+use crate::{
+    error::RestApiError,
+    lint::LintSeverity,
+    prelude::{Diff, Lint, LintCategory, RestApi, Transform},
+};
+use std::{collections::HashMap, ops::Range};
+
+/// Fixes lints of one Linter category.
+pub trait LintFixer: Send + Sync {
+    /// The category of lints this fixer handles.
+    fn category(&self) -> LintCategory;
+
+    /// Returns the replacement for `snippet`, the wikitext in the source range of `lint`,
+    /// or `None` if the lint can not be fixed automatically.
+    fn fix(&self, lint: &Lint, snippet: &str) -> Option<String>;
+}
+
+/// Adds the missing end tag at the end of the source range, for `missing-end-tag`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MissingEndTagFixer;
+
+impl LintFixer for MissingEndTagFixer {
+    fn category(&self) -> LintCategory {
+        LintCategory::MissingEndTag
+    }
+
+    fn fix(&self, lint: &Lint, snippet: &str) -> Option<String> {
+        let name = lint.params.name.as_deref()?;
+        let end_tag = format!("</{name}>");
+        if snippet.to_lowercase().ends_with(&end_tag.to_lowercase()) {
+            return None;
+        }
+        Some(format!("{snippet}{end_tag}"))
+    }
+}
+
+/// Replaces obsolete HTML tags with their modern equivalents, for `obsolete-tag`.
+/// Tags without a simple equivalent (eg `font`) are not fixed.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ObsoleteTagFixer;
+
+impl ObsoleteTagFixer {
+    /// Returns the replacement tag name and style for an obsolete tag.
+    fn replacement(name: &str) -> Option<(&'static str, Option<&'static str>)> {
+        match name {
+            "center" => Some(("div", Some("text-align: center;"))),
+            "big" => Some(("span", Some("font-size: larger;"))),
+            "strike" => Some(("s", None)),
+            "tt" => Some(("code", None)),
+            _ => None,
+        }
+    }
+}
+
+impl LintFixer for ObsoleteTagFixer {
+    fn category(&self) -> LintCategory {
+        LintCategory::ObsoleteTag
+    }
+
+    fn fix(&self, lint: &Lint, snippet: &str) -> Option<String> {
+        let name = lint.params.name.as_deref()?.to_lowercase();
+        let (new_name, style) = Self::replacement(&name)?;
+        let range = lint.source_range()?;
+        let open_width = range.open_width?;
+        let close_width = range.close_width.filter(|width| *width > 0)?;
+        let open_tag = snippet.get(..open_width)?;
+        let content = snippet.get(open_width..snippet.len().checked_sub(close_width)?)?;
+        let close_tag = snippet.get(snippet.len() - close_width..)?;
+        let lower_open_tag = open_tag.to_lowercase();
+        if !lower_open_tag.starts_with(&format!("<{name}"))
+            || !close_tag.eq_ignore_ascii_case(&format!("</{name}>"))
+        {
+            return None;
+        }
+        let attributes = open_tag.get(name.len() + 1..open_tag.len() - 1)?;
+        let style = match style {
+            // Merging style attributes is left to humans
+            Some(_) if lower_open_tag.contains("style") => return None,
+            Some(style) => format!(" style=\"{style}\""),
+            None => String::new(),
+        };
+        Some(format!(
+            "<{new_name}{attributes}{style}>{content}</{new_name}>"
+        ))
+    }
+}
+
+/// Removes the invalid options of an image, for `bogus-image-options`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BogusImageOptionsFixer;
+
+impl LintFixer for BogusImageOptionsFixer {
+    fn category(&self) -> LintCategory {
+        LintCategory::BogusImageOptions
+    }
+
+    fn fix(&self, lint: &Lint, snippet: &str) -> Option<String> {
+        let items = lint.params.items.as_ref()?;
+        let mut fixed = snippet.to_string();
+        for item in items {
+            let option = format!("|{item}");
+            let pos = fixed
+                .match_indices(&option)
+                .map(|(pos, _)| pos)
+                .find(|pos| {
+                    let after = &fixed[pos + option.len()..];
+                    after.starts_with('|') || after.starts_with("]]")
+                })?;
+            fixed.replace_range(pos..pos + option.len(), "");
+        }
+        (fixed != snippet).then_some(fixed)
+    }
+}
+
+/// A single fix applied to the wikitext.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintFix {
+    /// The fixed lint
+    pub lint: Lint,
+    /// Byte range in the original wikitext
+    pub range: Range<usize>,
+    /// Byte range in the patched wikitext
+    pub new_range: Range<usize>,
+    pub original: String,
+    pub replacement: String,
+}
+
+impl LintFix {
+    /// Creates a fix replacing `range` of `wikitext`, with the replacement starting at `new_start` in the patched wikitext.
+    fn new(
+        wikitext: &str,
+        lint: Lint,
+        range: Range<usize>,
+        replacement: String,
+        new_start: usize,
+    ) -> Self {
+        Self {
+            lint,
+            new_range: new_start..new_start + replacement.len(),
+            original: wikitext[range.clone()].to_string(),
+            range,
+            replacement,
+        }
+    }
+
+    /// Returns the category of the fixed lint.
+    pub const fn category(&self) -> &LintCategory {
+        self.lint.category()
+    }
+}
+
+/// A lint with its priority and, if a fixer can handle it, a suggested fix for its source range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintSuggestion {
+    pub lint: Lint,
+    pub severity: Option<LintSeverity>,
+    /// The suggested fix, on its own; `new_range` is the range of the replacement if only this fix is applied
+    pub fix: Option<LintFix>,
+}
+
+/// The result of fixing lints in wikitext.
+#[derive(Clone, Debug)]
+pub struct LintPatch {
+    /// The wikitext the lints were reported for
+    pub original: String,
+    /// The patched wikitext
+    pub wikitext: String,
+    pub fixes: Vec<LintFix>,
+    /// Lints that were not fixed, because there is no fixer for them, they come from a template,
+    /// the fixer could not handle them, they overlap another fix, or the fix could not be verified
+    pub skipped: Vec<Lint>,
+}
+
+impl LintPatch {
+    /// Returns `true` if no fixes were applied.
+    pub const fn is_empty(&self) -> bool {
+        self.fixes.is_empty()
+    }
+
+    /// Returns an edit summary listing the fixed categories, eg "Fixed lint errors: obsolete-tag (2)".
+    pub fn summary(&self) -> String {
+        let mut counts: Vec<(&LintCategory, usize)> = vec![];
+        for fix in &self.fixes {
+            match counts
+                .iter_mut()
+                .find(|(category, _)| *category == fix.category())
+            {
+                Some((_, count)) => *count += 1,
+                None => counts.push((fix.category(), 1)),
+            }
+        }
+        let parts: Vec<String> = counts
+            .iter()
+            .map(|(category, count)| format!("{category} ({count})"))
+            .collect();
+        format!("Fixed lint errors: {}", parts.join(", "))
+    }
+
+    /// Returns a local diff from the original to the patched wikitext.
+    pub fn diff(&self) -> Diff {
+        Diff::from_wikitext(&self.original, &self.wikitext)
+    }
+}
+
+/// A registry of `LintFixer`s, one per Linter category.
+pub struct LintFixerRegistry {
+    fixers: HashMap<LintCategory, Box<dyn LintFixer>>,
+}
+
+impl Default for LintFixerRegistry {
+    /// Creates a registry with all built-in fixers.
+    fn default() -> Self {
+        Self::new()
+            .with_fixer(MissingEndTagFixer)
+            .with_fixer(ObsoleteTagFixer)
+            .with_fixer(BogusImageOptionsFixer)
+    }
+}
+
+impl std::fmt::Debug for LintFixerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LintFixerRegistry")
+            .field("categories", &self.categories())
+            .finish()
+    }
+}
+
+impl LintFixerRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            fixers: HashMap::new(),
+        }
+    }
+
+    /// Adds a fixer, replacing any previous fixer for the same category.
+    pub fn with_fixer<F: LintFixer + 'static>(mut self, fixer: F) -> Self {
+        self.register(Box::new(fixer));
+        self
+    }
+
+    /// Adds a fixer, replacing any previous fixer for the same category.
+    pub fn register(&mut self, fixer: Box<dyn LintFixer>) {
+        self.fixers.insert(fixer.category(), fixer);
+    }
+
+    /// Returns the categories that have a fixer, sorted.
+    pub fn categories(&self) -> Vec<&LintCategory> {
+        let mut ret: Vec<_> = self.fixers.keys().collect();
+        ret.sort();
+        ret
+    }
+
+    /// Applies all available fixes for `lints` to `wikitext`, without verification.
+    /// Lints from templates are skipped, as their source range covers the transclusion.
+    pub fn fix(&self, wikitext: &str, lints: &[Lint]) -> LintPatch {
+        let mut candidates = vec![];
+        let mut skipped = vec![];
+        for lint in lints {
+            match self.fix_lint(wikitext, lint) {
+                Some(candidate) => candidates.push(candidate),
+                None => skipped.push(lint.to_owned()),
+            }
+        }
+        candidates.sort_by_key(|(_, range, _)| range.start);
+        let mut last_end = 0;
+        let mut accepted = vec![];
+        for (lint, range, replacement) in candidates {
+            if range.start < last_end {
+                skipped.push(lint);
+                continue;
+            }
+            last_end = range.end;
+            accepted.push((lint, range, replacement));
+        }
+        Self::build_patch(wikitext, accepted, skipped)
+    }
+
+    /// Returns every lint with its priority and, where a fixer handles it, the suggested replacement.
+    /// Unlike `fix`, overlapping suggestions are all kept, as they are meant to be applied one at a time.
+    pub fn suggest(&self, wikitext: &str, lints: &[Lint]) -> Vec<LintSuggestion> {
+        lints
+            .iter()
+            .map(|lint| LintSuggestion {
+                lint: lint.to_owned(),
+                severity: lint.severity(),
+                fix: self
+                    .fix_lint(wikitext, lint)
+                    .map(|(fixed, range, replacement)| {
+                        let new_start = range.start;
+                        LintFix::new(wikitext, fixed, range, replacement, new_start)
+                    }),
+            })
+            .collect()
+    }
+
+    /// Lints wikitext via `Transform::wikitext2lint_title`, using a title for context,
+    /// and returns every lint with its priority and, where a fixer handles it, the suggested replacement.
+    pub async fn wikitext2lint_suggestions_title(
+        &self,
+        api: &RestApi,
+        title: &str,
+        wikitext: &str,
+    ) -> Result<Vec<LintSuggestion>, RestApiError> {
+        let lints = Transform::wikitext2lint_title(title, wikitext, api).await?;
+        Ok(self.suggest(wikitext, &lints))
+    }
+
+    /// Applies all available fixes, then lints the patched wikitext via `Transform::wikitext2lint_title`,
+    /// and keeps only the fixes where no lint is reported in the patched range anymore.
+    /// If fixes were dropped, the remaining ones are applied and linted again, so the returned patch is always verified.
+    pub async fn fix_and_verify(
+        &self,
+        api: &RestApi,
+        title: &str,
+        wikitext: &str,
+        lints: &[Lint],
+    ) -> Result<LintPatch, RestApiError> {
+        let mut patch = self.fix(wikitext, lints);
+        while !patch.is_empty() {
+            let new_lints =
+                Transform::wikitext2lint_title(title, patch.wikitext.as_str(), api).await?;
+            let mut skipped = patch.skipped;
+            let mut accepted = vec![];
+            let mut verified = true;
+            for fix in patch.fixes {
+                let still_linted = new_lints.iter().any(|new_lint| {
+                    new_lint.source_range().is_some_and(|range| {
+                        range.start < fix.new_range.end && fix.new_range.start < range.end
+                    })
+                });
+                if still_linted {
+                    verified = false;
+                    skipped.push(fix.lint);
+                } else {
+                    accepted.push((fix.lint, fix.range, fix.replacement));
+                }
+            }
+            patch = Self::build_patch(wikitext, accepted, skipped);
+            if verified {
+                break;
+            }
+        }
+        Ok(patch)
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Returns the lint, its source range, and the replacement, if the lint can be fixed.
+    fn fix_lint(&self, wikitext: &str, lint: &Lint) -> Option<(Lint, Range<usize>, String)> {
+        if lint.template_info.is_some() {
+            return None;
+        }
+        let fixer = self.fixers.get(&lint.type_name)?;
+        let range = lint.source_range()?;
+        let snippet = lint.snippet(wikitext)?;
+        let replacement = fixer.fix(lint, snippet)?;
+        Some((lint.to_owned(), range.start..range.end, replacement))
+    }
+
+    /// Builds a patch from non-overlapping fixes, sorted by position.
+    fn build_patch(
+        wikitext: &str,
+        fixes: Vec<(Lint, Range<usize>, String)>,
+        skipped: Vec<Lint>,
+    ) -> LintPatch {
+        let mut patched = String::with_capacity(wikitext.len());
+        let mut last_end = 0;
+        let mut applied = vec![];
+        for (lint, range, replacement) in fixes {
+            patched += &wikitext[last_end..range.start];
+            let new_start = patched.len();
+            patched += &replacement;
+            last_end = range.end;
+            applied.push(LintFix::new(wikitext, lint, range, replacement, new_start));
+        }
+        patched += &wikitext[last_end..];
+        LintPatch {
+            original: wikitext.to_string(),
+            wikitext: patched,
+            fixes: applied,
+            skipped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::DiffLineType;
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn lint(category: &str, dsr: [Option<usize>; 4], params: serde_json::Value) -> Lint {
+        serde_json::from_value(json!({
+            "type": category,
+            "dsr": dsr,
+            "params": params,
+        }))
+        .expect("Failed to parse lint")
+    }
+
+    #[test]
+    fn test_suggest() {
+        let wikitext = "<center>a</center> <b>b";
+        let lints = [
+            lint(
+                "obsolete-tag",
+                [Some(0), Some(18), Some(8), Some(9)],
+                json!({"name": "center"}),
+            ),
+            lint(
+                "missing-end-tag",
+                [Some(19), Some(23), Some(3), Some(0)],
+                json!({"name": "b"}),
+            ),
+            lint("fostered", [Some(0), Some(18), None, None], json!({})),
+        ];
+        let suggestions = LintFixerRegistry::default().suggest(wikitext, &lints);
+        assert_eq!(suggestions.len(), 3);
+        let fix = suggestions[0].fix.as_ref().expect("Fix missing");
+        assert_eq!(fix.range, 0..18);
+        assert_eq!(
+            fix.replacement,
+            "<div style=\"text-align: center;\">a</div>"
+        );
+        assert_eq!(fix.new_range, 0..fix.replacement.len());
+        assert_eq!(suggestions[0].severity, Some(LintSeverity::Low));
+        let end_tag_fix = suggestions[1].fix.as_ref().expect("Fix missing");
+        assert_eq!(end_tag_fix.original, "<b>b");
+        assert_eq!(end_tag_fix.replacement, "<b>b</b>");
+        assert_eq!(suggestions[2].severity, Some(LintSeverity::Medium));
+        assert_eq!(suggestions[2].fix, None);
+    }
+
+    #[test]
+    fn test_missing_end_tag() {
+        let wikitext = "Foo <b>bar\n\nBaz";
+        let lints = [lint(
+            "missing-end-tag",
+            [Some(4), Some(10), Some(3), Some(0)],
+            json!({"name": "b"}),
+        )];
+        let patch = LintFixerRegistry::default().fix(wikitext, &lints);
+        assert_eq!(patch.wikitext, "Foo <b>bar</b>\n\nBaz");
+        assert_eq!(patch.fixes[0].new_range, 4..14);
+        assert!(patch.skipped.is_empty());
+        assert_eq!(patch.summary(), "Fixed lint errors: missing-end-tag (1)");
+    }
+
+    #[test]
+    fn test_obsolete_tag() {
+        let wikitext = "A <center class=\"x\">B</center> <tt>C</tt> <font color=red>D</font>";
+        let lints = [
+            lint(
+                "obsolete-tag",
+                [Some(2), Some(30), Some(18), Some(9)],
+                json!({"name": "center"}),
+            ),
+            lint(
+                "obsolete-tag",
+                [Some(31), Some(41), Some(4), Some(5)],
+                json!({"name": "tt"}),
+            ),
+            lint(
+                "obsolete-tag",
+                [Some(42), Some(66), Some(16), Some(7)],
+                json!({"name": "font"}),
+            ),
+        ];
+        let patch = LintFixerRegistry::default().fix(wikitext, &lints);
+        assert_eq!(
+            patch.wikitext,
+            "A <div class=\"x\" style=\"text-align: center;\">B</div> <code>C</code> <font color=red>D</font>"
+        );
+        assert_eq!(patch.fixes.len(), 2);
+        assert_eq!(patch.skipped.len(), 1);
+        assert_eq!(patch.summary(), "Fixed lint errors: obsolete-tag (2)");
+    }
+
+    #[test]
+    fn test_bogus_image_options() {
+        let wikitext = "[[File:Foo.jpg|thumb|upright=foo|left|Caption]]";
+        let lints = [lint(
+            "bogus-image-options",
+            [Some(0), Some(wikitext.len()), Some(2), Some(2)],
+            json!({"items": ["upright=foo"]}),
+        )];
+        let patch = LintFixerRegistry::default().fix(wikitext, &lints);
+        assert_eq!(patch.wikitext, "[[File:Foo.jpg|thumb|left|Caption]]");
+    }
+
+    #[test]
+    fn test_skipped() {
+        let wikitext = "<b><i>foo";
+        let mut template_lint = lint(
+            "missing-end-tag",
+            [Some(0), Some(9), Some(3), Some(0)],
+            json!({"name": "b"}),
+        );
+        template_lint.template_info = Some(crate::prelude::TemplateInfo {
+            name: "Template:Foo".to_string(),
+        });
+        let lints = [
+            template_lint,
+            lint("duplicate-ids", [Some(0), Some(9), None, None], json!({})),
+            lint(
+                "missing-end-tag",
+                [Some(0), Some(9), Some(3), Some(0)],
+                json!({"name": "b"}),
+            ),
+            // Overlaps the previous fix
+            lint(
+                "missing-end-tag",
+                [Some(3), Some(9), Some(3), Some(0)],
+                json!({"name": "i"}),
+            ),
+        ];
+        let registry = LintFixerRegistry::new().with_fixer(MissingEndTagFixer);
+        assert_eq!(registry.categories(), [&LintCategory::MissingEndTag]);
+        let patch = registry.fix(wikitext, &lints);
+        assert_eq!(patch.wikitext, "<b><i>foo</b>");
+        assert_eq!(patch.skipped.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_wikitext2lint_suggestions_title() {
+        let title = "Cambridge";
+        let wikitext = "== Hello Jupiter ==[[Link|text]]<table><table>";
+        let json_text: String =
+            std::fs::read_to_string("test_data/wikitext2lint.json").expect("Test file missing");
+        let lints: serde_json::Value =
+            serde_json::from_str(&json_text).expect("Failed to parse JSON");
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("w/rest.php/v1/transform/wikitext/to/lint/Cambridge"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(lints))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+
+        let suggestions = LintFixerRegistry::default()
+            .wikitext2lint_suggestions_title(&api, title, wikitext)
+            .await
+            .expect("Failed to lint wikitext");
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].severity, Some(LintSeverity::Low));
+        let fix = suggestions[0].fix.as_ref().expect("Fix missing");
+        assert_eq!(fix.range, 39..46);
+        assert_eq!(fix.replacement, "<table></table>");
+        assert_eq!(suggestions[1].severity, Some(LintSeverity::High));
+        assert_eq!(suggestions[1].fix, None);
+    }
+
+    #[tokio::test]
+    async fn test_fix_and_verify() {
+        let mock_server = MockServer::start().await;
+        // The fixed `<b>` is clean, the fixed `<s>` is still reported
+        let new_lints = json!([{
+            "type": "missing-end-tag",
+            "dsr": [20, 26, 3, 0],
+            "params": {"name": "s"},
+            "templateInfo": null
+        }]);
+        Mock::given(method("POST"))
+            .and(path("w/rest.php/v1/transform/wikitext/to/lint/Foo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(new_lints))
+            // The patch without the `<s>` fix is linted again
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let wikitext = "Foo <b>bar\n\nBaz <s>qux";
+        let lints = [
+            lint(
+                "missing-end-tag",
+                [Some(4), Some(10), Some(3), Some(0)],
+                json!({"name": "b"}),
+            ),
+            lint(
+                "missing-end-tag",
+                [Some(16), Some(22), Some(3), Some(0)],
+                json!({"name": "s"}),
+            ),
+        ];
+        let patch = LintFixerRegistry::default()
+            .fix_and_verify(&api, "Foo", wikitext, &lints)
+            .await
+            .expect("Failed to fix lints");
+        assert_eq!(patch.wikitext, "Foo <b>bar</b>\n\nBaz <s>qux");
+        assert_eq!(patch.fixes.len(), 1);
+        assert_eq!(patch.skipped.len(), 1);
+        assert_eq!(
+            patch
+                .diff()
+                .diff
+                .iter()
+                .filter(|d| d.line_type() == DiffLineType::Changed)
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fix_and_verify_relint() {
+        let mock_server = MockServer::start().await;
+        // The first patch is clean where `<b>` was fixed, but once the `<s>` fix is dropped, it is not
+        Mock::given(method("POST"))
+            .and(path("w/rest.php/v1/transform/wikitext/to/lint/Foo"))
+            .and(body_string_contains("qux</s>"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "type": "missing-end-tag",
+                "dsr": [20, 26, 3, 0],
+                "params": {"name": "s"},
+            }])))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("w/rest.php/v1/transform/wikitext/to/lint/Foo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "type": "missing-end-tag",
+                "dsr": [4, 14, 3, 4],
+                "params": {"name": "b"},
+            }])))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let wikitext = "Foo <b>bar\n\nBaz <s>qux";
+        let lints = [
+            lint(
+                "missing-end-tag",
+                [Some(4), Some(10), Some(3), Some(0)],
+                json!({"name": "b"}),
+            ),
+            lint(
+                "missing-end-tag",
+                [Some(16), Some(22), Some(3), Some(0)],
+                json!({"name": "s"}),
+            ),
+        ];
+        let patch = LintFixerRegistry::default()
+            .fix_and_verify(&api, "Foo", wikitext, &lints)
+            .await
+            .expect("Failed to fix lints");
+        assert!(patch.is_empty());
+        assert_eq!(patch.wikitext, wikitext);
+        assert_eq!(patch.skipped.len(), 2);
+    }
+}
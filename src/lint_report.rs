@@ -0,0 +1,255 @@
+use crate::{
+    prelude::{Lint, LintCategory, Page, RestApi},
+    utilities::csv_field,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+
+/// The number of lints for a category, template or page.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintCount {
+    pub key: String,
+    pub count: usize,
+}
+
+/// Lints of many pages, with aggregation by category, template and page.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LintReport {
+    /// Lints by page title
+    pub pages: BTreeMap<String, Vec<Lint>>,
+    /// Error messages by page title, for pages whose lints could not be retrieved
+    pub errors: BTreeMap<String, String>,
+}
+
+impl LintReport {
+    /// Creates an empty report.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retrieves the lints of all pages in `titles` (eg from `Search::page` or a file),
+    /// with at most `concurrency` requests running in parallel.
+    /// Pages that fail are recorded in `errors` and do not abort the scan.
+    pub async fn scan<I, S>(api: &RestApi, titles: I, concurrency: usize) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut ret = Self::new();
+        let mut results = Page::get_lint_many(api, titles, false, concurrency, false);
+        while let Some((title, result)) = results.next().await {
+            match result {
+                Ok(lints) => ret.add(title, lints),
+                Err(e) => {
+                    ret.errors.insert(title, e.to_string());
+                }
+            }
+        }
+        ret
+    }
+
+    /// Adds the lints of a page.
+    pub fn add<S: Into<String>>(&mut self, title: S, lints: Vec<Lint>) {
+        self.pages.entry(title.into()).or_default().extend(lints);
+    }
+
+    /// Returns the total number of lints.
+    pub fn total(&self) -> usize {
+        self.pages.values().map(Vec::len).sum()
+    }
+
+    /// Returns the number of lints per category, most frequent first.
+    pub fn by_category(&self) -> Vec<LintCount> {
+        Self::count(self.lints(None).map(|(_, lint)| lint.type_name.to_string()))
+    }
+
+    /// Returns the number of lints per template, optionally only for one category, most frequent first.
+    /// Lints that do not come from a template are not counted.
+    pub fn by_template(&self, category: Option<&LintCategory>) -> Vec<LintCount> {
+        Self::count(self.lints(category).filter_map(|(_, lint)| {
+            lint.template_info
+                .as_ref()
+                .map(|template| template.name.to_owned())
+        }))
+    }
+
+    /// Returns the number of lints per page, optionally only for one category, most frequent first.
+    pub fn by_page(&self, category: Option<&LintCategory>) -> Vec<LintCount> {
+        Self::count(self.lints(category).map(|(title, _)| title.to_owned()))
+    }
+
+    /// Returns all lints as CSV, one row per lint, with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut ret = "page,category,template,start,end\n".to_string();
+        for (title, lint) in self.lints(None) {
+            let range = lint.source_range();
+            let row = [
+                title.to_string(),
+                lint.type_name.to_string(),
+                lint.template_info
+                    .as_ref()
+                    .map(|template| template.name.to_owned())
+                    .unwrap_or_default(),
+                range
+                    .map(|range| range.start.to_string())
+                    .unwrap_or_default(),
+                range.map(|range| range.end.to_string()).unwrap_or_default(),
+            ];
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            ret += &row.join(",");
+            ret.push('\n');
+        }
+        ret
+    }
+
+    /// Returns counts as CSV, with a header row.
+    pub fn counts_to_csv(key_name: &str, counts: &[LintCount]) -> String {
+        let mut ret = format!("{},count\n", csv_field(key_name));
+        for count in counts {
+            ret += &format!("{},{}\n", csv_field(&count.key), count.count);
+        }
+        ret
+    }
+
+    /// Returns the aggregated counts by category, template and page, and the errors, as JSON.
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "total": self.total(),
+            "categories": self.by_category(),
+            "templates": self.by_template(None),
+            "pages": self.by_page(None),
+            "errors": self.errors,
+        })
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Iterates over all `(title, lint)` pairs, optionally only for one category.
+    fn lints<'a>(
+        &'a self,
+        category: Option<&'a LintCategory>,
+    ) -> impl Iterator<Item = (&'a str, &'a Lint)> + 'a {
+        self.pages
+            .iter()
+            .flat_map(|(title, lints)| lints.iter().map(move |lint| (title.as_str(), lint)))
+            .filter(move |(_, lint)| category.is_none_or(|category| lint.type_name == *category))
+    }
+
+    /// Counts the keys, most frequent first, then by key.
+    fn count<I: Iterator<Item = String>>(keys: I) -> Vec<LintCount> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for key in keys {
+            *counts.entry(key).or_default() += 1;
+        }
+        let mut ret: Vec<LintCount> = counts
+            .into_iter()
+            .map(|(key, count)| LintCount { key, count })
+            .collect();
+        ret.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn read_lints(test_file: &str) -> Vec<Lint> {
+        let test_text: String =
+            std::fs::read_to_string(format!("test_data/{test_file}")).expect("Test file missing");
+        serde_json::from_str(&test_text).expect("Failed to parse JSON")
+    }
+
+    fn test_report() -> LintReport {
+        let mut report = LintReport::new();
+        report.add(
+            "Rust (programming language)",
+            read_lints("revision_lint.json"),
+        );
+        report.add("Foo, \"bar\"", read_lints("wikitext2lint.json"));
+        report
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let report = test_report();
+        assert_eq!(report.total(), 16);
+        let categories = report.by_category();
+        assert_eq!(categories[0].key, "duplicate-ids");
+        assert_eq!(categories[0].count, 14);
+        assert_eq!(categories.len(), 3);
+        let templates = report.by_template(Some(&LintCategory::DuplicateIds));
+        assert_eq!(templates[0].key, "Template:Cite_web");
+        assert_eq!(templates.iter().map(|t| t.count).sum::<usize>(), 14);
+        assert!(
+            report
+                .by_template(Some(&LintCategory::MissingEndTag))
+                .is_empty()
+        );
+        let pages = report.by_page(Some(&LintCategory::MissingEndTag));
+        assert_eq!(
+            pages,
+            [LintCount {
+                key: "Foo, \"bar\"".to_string(),
+                count: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_export() {
+        let report = test_report();
+        let csv = report.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("page,category,template,start,end"));
+        assert_eq!(
+            lines.next(),
+            Some("\"Foo, \"\"bar\"\"\",missing-end-tag,,39,46")
+        );
+        assert_eq!(csv.lines().count(), 17);
+        let counts = LintReport::counts_to_csv("template", &report.by_template(None));
+        assert!(counts.starts_with("template,count\nTemplate:Cite_web,"));
+        let json = report.to_json();
+        assert_eq!(json["total"], 16);
+        assert_eq!(json["categories"][0]["key"], "duplicate-ids");
+    }
+
+    #[tokio::test]
+    async fn test_scan() {
+        let mock_server = MockServer::start().await;
+        let lints: Value = serde_json::from_str(
+            &std::fs::read_to_string("test_data/page_lint.json").expect("Test file missing"),
+        )
+        .expect("Failed to parse JSON");
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/page/Foo/lint"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(lints))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/page/Bar/lint"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let report = LintReport::scan(&api, ["Foo", "Bar"], 2).await;
+        assert_eq!(report.pages.len(), 1);
+        assert_eq!(report.pages["Foo"].len(), 9);
+        assert!(report.errors.contains_key("Bar"));
+        assert_eq!(
+            report.by_template(None)[0],
+            LintCount {
+                key: "Template:Cite_book".to_string(),
+                count: 9
+            }
+        );
+    }
+}
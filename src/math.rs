@@ -6,7 +6,7 @@ pub struct Math;
 
 impl Math {
     /// Retrieves the HTML popup information from Wikidata for a given QID.
-    pub async fn popup_html(qid: usize, api: &RestApi) -> Result<PopupInfo, RestApiError> {
+    pub async fn popup_html(qid: ItemId, api: &RestApi) -> Result<PopupInfo, RestApiError> {
         let path = format!("/math/v0/popup/html/{qid}");
         let params = HashMap::new();
         let request = api
@@ -58,7 +58,7 @@ mod tests {
         let (api, _mock_server) =
             get_mock_api("math_popup_html.json", "math/v0/popup/html/12345").await;
         // let api = crate::rest_api_builder::RestApiBuilder::wikipedia("en").build();
-        let popup = Math::popup_html(12345, &api)
+        let popup = Math::popup_html(ItemId(12345), &api)
             .await
             .expect("Failed to get page content");
         assert_eq!(popup.title, "Count von Count");
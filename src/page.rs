@@ -1,4 +1,10 @@
-use crate::{error::RestApiError, prelude::*};
+use crate::{
+    blame::Blame,
+    bulk::{BulkStream, run_concurrent},
+    error::RestApiError,
+    prelude::*,
+};
+use futures::{Stream, StreamExt};
 use serde_json::{Value, from_value, json};
 use std::collections::HashMap;
 use urlencoding::encode;
@@ -39,6 +45,99 @@ impl Page {
         Ok((ret, wikitext))
     }
 
+    /// Retrieves basic page information and the wikitext, split into sections.
+    pub async fn get_sections(
+        &self,
+        api: &RestApi,
+        follow_redirect: bool,
+    ) -> Result<(PageInfo, WikitextSections), RestApiError> {
+        let (page_info, wikitext) = self.get(api, follow_redirect).await?;
+        Ok((page_info, WikitextSections::parse(wikitext)))
+    }
+
+    /// Retrieves the prose of the page by section, from its Parsoid HTML,
+    /// without the elements and sections removed by `options`.
+    #[cfg(feature = "html")]
+    pub async fn get_plaintext(
+        &self,
+        api: &RestApi,
+        follow_redirect: bool,
+        options: &PlaintextOptions,
+    ) -> Result<Plaintext, RestApiError> {
+        let html = self
+            .get_html(api, follow_redirect, false, HtmlFlavor::View)
+            .await?;
+        Ok(ParsoidDocument::parse(&html).plaintext(options))
+    }
+
+    /// Retrieves basic page information and the references in the wikitext, as citations.
+    /// Use `ParsoidDocument::citations` on `Page::get_html` for the rendered reference texts.
+    pub async fn get_citations(
+        &self,
+        api: &RestApi,
+        follow_redirect: bool,
+    ) -> Result<(PageInfo, Vec<Citation>), RestApiError> {
+        let (page_info, wikitext) = self.get(api, follow_redirect).await?;
+        Ok((page_info, Citation::from_wikitext(&wikitext)))
+    }
+
+    /// Retrieves basic page information and the wikitext, parsed into nodes with byte spans.
+    pub async fn get_parsed(
+        &self,
+        api: &RestApi,
+        follow_redirect: bool,
+    ) -> Result<(PageInfo, Wikitext), RestApiError> {
+        let (page_info, wikitext) = self.get(api, follow_redirect).await?;
+        Ok((page_info, Wikitext::parse(wikitext)))
+    }
+
+    /// Retrieves basic page information and wikitext for many pages,
+    /// with at most `concurrency` requests running in parallel.
+    /// Yields `(title, result)` pairs in completion order, or in input order if `preserve_order` is set.
+    /// Errors are reported per page and do not abort the batch.
+    pub fn get_many<'a, I, S>(
+        api: &'a RestApi,
+        titles: I,
+        follow_redirect: bool,
+        concurrency: usize,
+        preserve_order: bool,
+    ) -> BulkStream<'a, String, (PageInfo, String)>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let titles = titles.into_iter().map(Into::into).collect();
+        run_concurrent(
+            titles,
+            concurrency,
+            preserve_order,
+            move |title| async move { Page::new(title).get(api, follow_redirect).await },
+        )
+    }
+
+    /// Retrieves lint data for many pages, with at most `concurrency` requests running in parallel.
+    /// Yields `(title, result)` pairs in completion order, or in input order if `preserve_order` is set.
+    /// Errors are reported per page and do not abort the batch.
+    pub fn get_lint_many<'a, I, S>(
+        api: &'a RestApi,
+        titles: I,
+        follow_redirect: bool,
+        concurrency: usize,
+        preserve_order: bool,
+    ) -> BulkStream<'a, String, Vec<Lint>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let titles = titles.into_iter().map(Into::into).collect();
+        run_concurrent(
+            titles,
+            concurrency,
+            preserve_order,
+            move |title| async move { Page::new(title).get_lint(api, follow_redirect).await },
+        )
+    }
+
     /// Retrieves basic page information and the URL for HTML retrieval.
     pub async fn get_bare(
         &self,
@@ -84,6 +183,36 @@ impl Page {
         Ok(ret)
     }
 
+    /// Retrieves the HTML for many pages, with at most `concurrency` requests running in parallel.
+    /// Yields `(title, result)` pairs in completion order, or in input order if `preserve_order` is set.
+    /// Errors are reported per page and do not abort the batch.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_html_many<'a, I, S>(
+        api: &'a RestApi,
+        titles: I,
+        follow_redirect: bool,
+        stash: bool,
+        flavor: HtmlFlavor,
+        concurrency: usize,
+        preserve_order: bool,
+    ) -> BulkStream<'a, String, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let titles = titles.into_iter().map(Into::into).collect();
+        run_concurrent(
+            titles,
+            concurrency,
+            preserve_order,
+            move |title| async move {
+                Page::new(title)
+                    .get_html(api, follow_redirect, stash, flavor)
+                    .await
+            },
+        )
+    }
+
     /// Retrieves basic page information and the HTML for the page.
     pub async fn get_with_html(
         &self,
@@ -158,13 +287,29 @@ impl Page {
         Ok(ret)
     }
 
+    /// Fixes lint errors in the current wikitext with the fixers in `registry`, verifying each fix by linting the result.
+    /// The `latest` revision of the returned page info can be used as base revision,
+    /// to save the patched wikitext with `Page::edit`.
+    pub async fn fix_lints(
+        &self,
+        api: &RestApi,
+        registry: &LintFixerRegistry,
+    ) -> Result<(PageInfo, LintPatch), RestApiError> {
+        let (page_info, wikitext) = self.get(api, false).await?;
+        let lints = Revision::new(page_info.latest.id).get_lint(api).await?;
+        let patch = registry
+            .fix_and_verify(api, &page_info.title, &wikitext, &lints)
+            .await?;
+        Ok((page_info, patch))
+    }
+
     /// Retrieves history data for the page.
     pub async fn get_history(
         &self,
         api: &RestApi,
         filter: Option<Filter>,
-        older_than: Option<usize>,
-        newer_than: Option<usize>,
+        older_than: Option<RevisionId>,
+        newer_than: Option<RevisionId>,
     ) -> Result<History, RestApiError> {
         let path = format!("/page/{}/history", encode(&self.title));
         let mut params = HashMap::new();
@@ -186,13 +331,124 @@ impl Page {
         Ok(ret)
     }
 
+    /// Retrieves the complete history of the page, newest revision first, by following the older history segments.
+    /// If `newer_than` is set, only revisions newer than that revision are retrieved,
+    /// by following the newer history segments from that revision.
+    pub async fn get_full_history(
+        &self,
+        api: &RestApi,
+        filter: Option<Filter>,
+        newer_than: Option<RevisionId>,
+    ) -> Result<Vec<HistoryRevisionInfo>, RestApiError> {
+        if let Some(newer_than) = newer_than {
+            return self.get_newer_history(api, filter, newer_than).await;
+        }
+        let mut ret = vec![];
+        let mut older_than = None;
+        loop {
+            let history = self.get_history(api, filter, older_than, None).await?;
+            older_than = history.older_than();
+            ret.extend(history.revisions);
+            if older_than.is_none() {
+                return Ok(ret);
+            }
+        }
+    }
+
+    /// Retrieves all revisions of the page between `from` and `to` (both inclusive), with their content, oldest first.
+    /// The range is determined by walking the page history, then the revisions are retrieved
+    /// with at most `concurrency` requests in parallel.
+    /// Returns `RevisionNotOnPage` if `to` is the ID of a revision of another page.
+    pub async fn revisions_between<'a>(
+        &self,
+        api: &'a RestApi,
+        from: RevisionBound,
+        to: RevisionBound,
+        concurrency: usize,
+    ) -> Result<BulkStream<'a, RevisionId, (RevisionInfo, String)>, RestApiError> {
+        let mut ids = vec![];
+        // A revision ID as upper bound can be used as history cursor directly, once it is known to be of this page
+        let mut older_than = match to {
+            RevisionBound::Id(id) => {
+                let (revision_info, _) = Revision::new(id).get_bare(api).await?;
+                self.check_revision_page(&revision_info)?;
+                if from.cmp(id, &revision_info.timestamp).is_ge() {
+                    ids.push(id);
+                }
+                Some(id)
+            }
+            RevisionBound::Timestamp(_) => None,
+        };
+        'walk: loop {
+            let history = self.get_history(api, None, older_than, None).await?;
+            older_than = history.older_than();
+            for revision in &history.revisions {
+                if from.cmp_revision(revision).is_lt() {
+                    break 'walk;
+                }
+                if to.cmp_revision(revision).is_le() {
+                    ids.push(revision.id);
+                }
+            }
+            if older_than.is_none() {
+                break;
+            }
+        }
+        ids.reverse();
+        Ok(Revision::get_many(api, ids, concurrency, true))
+    }
+
+    /// Computes the line-level authorship of the current wikitext, by walking the full page history.
+    /// Revision contents are retrieved with at most `concurrency` requests in parallel.
+    pub async fn blame(&self, api: &RestApi, concurrency: usize) -> Result<Blame, RestApiError> {
+        let history = self.get_full_history(api, None, None).await?;
+        Self::apply_history(api, None, &history, concurrency).await
+    }
+
+    /// Brings a previously computed (eg cached) blame up to date with the latest revision of the page.
+    pub async fn update_blame(
+        &self,
+        api: &RestApi,
+        blame: Blame,
+        concurrency: usize,
+    ) -> Result<Blame, RestApiError> {
+        let history = self
+            .get_full_history(api, None, Some(blame.revision))
+            .await?;
+        Self::apply_history(api, Some(blame), &history, concurrency).await
+    }
+
+    /// Detects identity reverts in the full page history, oldest first.
+    /// A revision is a revert if its content matches one of the previous `search_radius` revisions
+    /// (see `DEFAULT_REVERT_SEARCH_RADIUS`).
+    /// Revision contents are retrieved with at most `concurrency` requests in parallel.
+    /// Revisions whose content can not be retrieved are passed to `RevertDetector::push_unavailable`.
+    pub async fn get_reverts(
+        &self,
+        api: &RestApi,
+        search_radius: usize,
+        concurrency: usize,
+    ) -> Result<Vec<Revert>, RestApiError> {
+        let history = self.get_full_history(api, None, None).await?;
+        let mut contents = Self::history_contents(api, &history, concurrency);
+        let mut detector = RevertDetector::new(search_radius);
+        let mut ret = vec![];
+        while let Some((revision, result)) = contents.next().await {
+            match result {
+                Ok(wikitext) => ret.extend(detector.push(revision, &wikitext)),
+                Err(_) => detector.push_unavailable(revision),
+            }
+        }
+        Ok(ret)
+    }
+
     /// Retrieves history counts for the page.
     pub async fn get_history_counts(
         &self,
         api: &RestApi,
         filter: HistoryFilterExtended,
-        from: Option<usize>,
-        to: Option<usize>,
+        from: Option<RevisionId>,
+        to: Option<RevisionId>,
     ) -> Result<HistoryCounts, RestApiError> {
         let path = format!("/page/{}/history/counts/{filter}", encode(&self.title));
         let mut params = HashMap::new();
@@ -211,6 +467,15 @@ impl Page {
         Ok(ret)
     }
 
+    /// Computes a local diff between the current wikitext of the page and `source`,
+    /// to preview an edit before saving it.
+    pub async fn preview_edit(&self, api: &RestApi, source: &str) -> Result<Diff, RestApiError> {
+        let (page_info, wikitext) = self.get(api, false).await?;
+        let mut diff = Diff::from_wikitext(&wikitext, source);
+        diff.from.id = page_info.latest.id;
+        Ok(diff)
+    }
+
     /// Replaces the contents of the page.
     pub async fn edit(
         &self,
@@ -248,6 +513,166 @@ impl Page {
         Ok((ret, wikitext))
     }
 
+    /// Appends a new level 2 section to the end of the page.
+    pub async fn append_section(
+        &self,
+        api: &RestApi,
+        title: &str,
+        body: &str,
+        comment: &str,
+    ) -> Result<(PageInfo, String), RestApiError> {
+        let (page_info, mut sections) = self.get_sections(api, false).await?;
+        sections.append_section(title, 2, body);
+        self.edit(api, &page_info.latest, sections.wikitext(), comment)
+            .await
+    }
+
+    /// Replaces a section of the page, including its heading and subsections, with new wikitext.
+    /// Section 0 is the lead, see `WikitextSections::replace_section`.
+    pub async fn replace_section(
+        &self,
+        api: &RestApi,
+        number: usize,
+        text: &str,
+        comment: &str,
+    ) -> Result<(PageInfo, String), RestApiError> {
+        let (page_info, mut sections) = self.get_sections(api, false).await?;
+        sections.replace_section(number, text)?;
+        self.edit(api, &page_info.latest, sections.wikitext(), comment)
+            .await
+    }
+
+    /// Inserts a new level 2 section before the section with the given number (1-based),
+    /// see `WikitextSections::insert_section`.
+    pub async fn insert_section(
+        &self,
+        api: &RestApi,
+        number: usize,
+        title: &str,
+        body: &str,
+        comment: &str,
+    ) -> Result<(PageInfo, String), RestApiError> {
+        let (page_info, mut sections) = self.get_sections(api, false).await?;
+        sections.insert_section(number, title, 2, body)?;
+        self.edit(api, &page_info.latest, sections.wikitext(), comment)
+            .await
+    }
+
+    /// Replaces the contents of the page with modified Parsoid HTML.
+    /// `html` is expected to be based on `original_html`, the `HtmlFlavor::Edit` HTML of `base_revision`,
+    /// and `etag` the `ETag` it was served with, as returned by `Revision::get_html_with_etag`.
+    /// The HTML is converted to wikitext using selective serialization against that original,
+    /// so unchanged parts of the page keep their original wikitext formatting.
+    pub async fn edit_html(
+        &self,
+        api: &RestApi,
+        html: &str,
+        base_revision: &RevisionTimestamp,
+        original_html: &str,
+        etag: Option<&str>,
+        comment: &str,
+    ) -> Result<(PageInfo, String), RestApiError> {
+        let wikitext = Transform::html2wikitext_selser(
+            html,
+            &self.title,
+            base_revision.id,
+            original_html,
+            etag,
+            api,
+        )
+        .await?;
+        self.edit(api, base_revision, &wikitext, comment).await
+    }
+
+    /// Edits the wikitext of the current revision of the page offline, via a `WikitextEditor`.
+    /// `f` makes changes to the parsed wikitext; every byte outside of the changed spans is saved unchanged.
+    /// The edit is based on the revision that was changed, so a concurrent edit results in an edit conflict.
+    /// Returns `None` if `f` made no changes, and the page was not edited.
+    pub async fn edit_wikitext<F>(
+        &self,
+        api: &RestApi,
+        comment: &str,
+        f: F,
+    ) -> Result<Option<(PageInfo, String)>, RestApiError>
+    where
+        F: FnOnce(&mut WikitextEditor),
+    {
+        let (page_info, wikitext) = self.get_parsed(api, false).await?;
+        let mut editor = WikitextEditor::new(&wikitext);
+        f(&mut editor);
+        if editor.is_empty() {
+            return Ok(None);
+        }
+        let ret = self
+            .edit(api, &page_info.latest, &editor.apply(), comment)
+            .await?;
+        Ok(Some(ret))
+    }
+
+    /// Edits the calls of the template `name` in the current revision of the page, via its Parsoid `data-mw`.
+    /// `f` is called for every template call (see `ParsoidDocument::edit_templates`), and returns `true` if it changed the call.
+    /// The page is saved with selective serialization, so the wikitext outside of the changed templates is preserved.
+    /// Returns `None` if no template call was changed, and the page was not edited.
+    #[cfg(feature = "html")]
+    pub async fn edit_templates<F>(
+        &self,
+        api: &RestApi,
+        name: &str,
+        comment: &str,
+        f: F,
+    ) -> Result<Option<(PageInfo, String)>, RestApiError>
+    where
+        F: FnMut(&mut TemplateCall) -> bool,
+    {
+        let (page_info, _) = self.get_bare(api, false).await?;
+        let base_revision = page_info.latest;
+        let (original_html, etag) = Revision::new(base_revision.id)
+            .get_html_with_etag(api, true, HtmlFlavor::Edit)
+            .await?;
+        let mut document = ParsoidDocument::parse(&original_html);
+        if document.edit_templates(name, f) == 0 {
+            return Ok(None);
+        }
+        let ret = self
+            .edit_html(
+                api,
+                &document.html(),
+                &base_revision,
+                &original_html,
+                etag.as_deref(),
+                comment,
+            )
+            .await?;
+        Ok(Some(ret))
+    }
+
+    /// Restores the content of an earlier revision of this page.
+    /// The edit is based on the latest revision, so a concurrent edit results in an edit conflict rather than being overwritten.
+    /// If the revision already is the latest one, the page is left unchanged.
+    /// The edit summary names the restored revision, with `comment` appended if it is not empty.
+    pub async fn rollback_to(
+        &self,
+        api: &RestApi,
+        revision: &Revision,
+        comment: &str,
+    ) -> Result<(PageInfo, String), RestApiError> {
+        let (revision_info, wikitext) = revision.get(api).await?;
+        self.check_revision_page(&revision_info)?;
+        let (page_info, current_wikitext) = self.get(api, false).await?;
+        if page_info.latest.id == revision.id() {
+            return Ok((page_info, current_wikitext));
+        }
+        let user = &revision_info.user.name;
+        let summary = Revision::revert_summary(
+            &format!(
+                "Revert to revision {} by [[Special:Contributions/{user}|{user}]]",
+                revision.id()
+            ),
+            comment,
+        );
+        self.edit(api, &page_info.latest, &wikitext, &summary).await
+    }
+
     /// Creates the page.
     pub async fn create(
         &self,
@@ -283,12 +708,98 @@ impl Page {
         let ret = from_value::<PageInfo>(j)?;
         Ok((ret, wikitext))
     }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Returns `RevisionNotOnPage` if the revision does not belong to this page.
+    fn check_revision_page(&self, revision_info: &RevisionInfo) -> Result<(), RestApiError> {
+        if revision_info.page.title != self.title && revision_info.page.key != self.title {
+            return Err(RestApiError::RevisionNotOnPage {
+                revision: revision_info.id,
+                title: self.title.to_owned(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies revisions from the history (newest first) to a blame, oldest first.
+    /// Revisions whose content can not be retrieved are skipped, and listed in `Blame::skipped`.
+    async fn apply_history(
+        api: &RestApi,
+        mut blame: Option<Blame>,
+        history: &[HistoryRevisionInfo],
+        concurrency: usize,
+    ) -> Result<Blame, RestApiError> {
+        let mut contents = Self::history_contents(api, history, concurrency);
+        let mut skipped = vec![];
+        while let Some((revision, result)) = contents.next().await {
+            let Ok(wikitext) = result else {
+                skipped.push(revision.id);
+                continue;
+            };
+            blame = Some(blame.map_or_else(
+                || Blame::new(revision, &wikitext),
+                |blame| blame.apply(revision, &wikitext),
+            ));
+        }
+        let mut ret = blame.ok_or(RestApiError::MissingResults)?;
+        for id in skipped {
+            // Unavailable revisions are retrieved again on every update
+            if !ret.skipped.contains(&id) {
+                ret.skipped.push(id);
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Retrieves the revisions newer than `newer_than`, newest first.
+    /// History segments are retrieved from the oldest to the newest, each segment being newest first.
+    async fn get_newer_history(
+        &self,
+        api: &RestApi,
+        filter: Option<Filter>,
+        newer_than: RevisionId,
+    ) -> Result<Vec<HistoryRevisionInfo>, RestApiError> {
+        let mut segments = vec![];
+        let mut cursor = newer_than;
+        loop {
+            let history = self.get_history(api, filter, None, Some(cursor)).await?;
+            let next = history.newer_than();
+            segments.push(history.revisions);
+            match next {
+                Some(next) if next > cursor => cursor = next,
+                _ => break,
+            }
+        }
+        Ok(segments
+            .into_iter()
+            .rev()
+            .flatten()
+            .filter(|revision| revision.id > newer_than)
+            .collect())
+    }
+
+    /// Retrieves the content of the revisions of a history (newest first),
+    /// and yields every revision with its wikitext, oldest first.
+    fn history_contents<'a>(
+        api: &'a RestApi,
+        history: &'a [HistoryRevisionInfo],
+        concurrency: usize,
+    ) -> impl Stream<Item = (&'a HistoryRevisionInfo, Result<String, RestApiError>)> + 'a {
+        let ids: Vec<RevisionId> = history.iter().rev().map(|revision| revision.id).collect();
+        Revision::get_many(api, ids, concurrency, true)
+            .zip(futures::stream::iter(history.iter().rev()))
+            .map(|((_, result), revision)| (revision, result.map(|(_, wikitext)| wikitext)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path};
+    use wiremock::matchers::{
+        body_partial_json, body_string_contains, header, method, path, path_regex, query_param,
+    };
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     async fn get_mock_api(test_file: &str, test_path: &str) -> (RestApi, MockServer) {
@@ -331,10 +842,156 @@ mod tests {
             .get(&api, false)
             .await
             .expect("Failed to get page content");
-        assert_eq!(page_info.id, 29414838);
+        assert_eq!(page_info.id, PageId(29414838));
         assert!(wikitext.contains("Mozilla sponsorship"));
     }
 
+    #[tokio::test]
+    async fn test_get_many() {
+        let title = "Rust (programming language)";
+        let (api, _mock_server) =
+            get_mock_api("page_get.json", &format!("/page/{}", encode(title))).await;
+        let titles = [title, "No such page", title];
+        let results: Vec<_> = Page::get_many(&api, titles, false, 2, true).collect().await;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, title);
+        let (page_info, _) = results[0].1.as_ref().expect("Failed to get page content");
+        assert_eq!(page_info.id, PageId(29414838));
+        assert_eq!(results[1].0, "No such page");
+        assert!(results[1].1.is_err());
+        assert!(results[2].1.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_many_unordered() {
+        let title = "Rust (programming language)";
+        let (api, _mock_server) =
+            get_mock_api("page_get.json", &format!("/page/{}", encode(title))).await;
+        let titles = vec![title; 5];
+        let results: Vec<_> = Page::get_many(&api, titles, false, 0, false)
+            .collect()
+            .await;
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_get_html_many() {
+        let title = "Rust (programming language)";
+        let (api, _mock_server) = get_mock_api(
+            "page_get_html.html",
+            &format!("/page/{}/html", encode(title)),
+        )
+        .await;
+        let results: Vec<_> = Page::get_html_many(
+            &api,
+            [title, title],
+            false,
+            false,
+            HtmlFlavor::View,
+            2,
+            true,
+        )
+        .collect()
+        .await;
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, result)| {
+            result
+                .as_ref()
+                .is_ok_and(|html| html.contains("<title>Rust (programming language)</title>"))
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_get_parsed() {
+        let (api, _mock_server) = get_mock_api(
+            "page_get.json",
+            &format!("/page/{}", encode("Rust (programming language)")),
+        )
+        .await;
+        let page = Page::new("Rust (programming language)");
+        let (page_info, wikitext) = page
+            .get_parsed(&api, false)
+            .await
+            .expect("Failed to get parsed page");
+        assert_eq!(page_info.id, PageId(29414838));
+        assert!(wikitext.source().starts_with("{{Short description"));
+        assert_eq!(wikitext.templates()[0].name, "Short description");
+    }
+
+    #[tokio::test]
+    async fn test_get_citations() {
+        let (api, _mock_server) = get_mock_api(
+            "page_get.json",
+            &format!("/page/{}", encode("Rust (programming language)")),
+        )
+        .await;
+        let page = Page::new("Rust (programming language)");
+        let (page_info, citations) = page
+            .get_citations(&api, false)
+            .await
+            .expect("Failed to get citations");
+        assert_eq!(page_info.id, PageId(29414838));
+        assert_eq!(citations.len(), 193);
+        assert!(citations.iter().any(|citation| {
+            citation
+                .templates
+                .iter()
+                .any(|template| template.name == "Cite book")
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_get_sections() {
+        let (api, _mock_server) = get_mock_api(
+            "page_get.json",
+            &format!("/page/{}", encode("Rust (programming language)")),
+        )
+        .await;
+        let page = Page::new("Rust (programming language)");
+        let (page_info, sections) = page
+            .get_sections(&api, false)
+            .await
+            .expect("Failed to get page content");
+        assert_eq!(page_info.id, PageId(29414838));
+        let history = sections.find("History").expect("History section missing");
+        assert_eq!(sections.section(history).map(|s| s.level), Some(2));
+        assert!(
+            sections
+                .section_text(0)
+                .is_some_and(|lead| lead.contains("{{Infobox"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_preview_edit() {
+        let (api, _mock_server) = get_mock_api(
+            "page_get.json",
+            &format!("/page/{}", encode("Rust (programming language)")),
+        )
+        .await;
+        let page = Page::new("Rust (programming language)");
+        let (_, wikitext) = page
+            .get(&api, false)
+            .await
+            .expect("Failed to get page content");
+        let source = wikitext.replace("Mozilla sponsorship", "Mozilla years");
+        let diff = page
+            .preview_edit(&api, &source)
+            .await
+            .expect("Failed to preview edit");
+        assert_eq!(diff.from.id, RevisionId(1318205510));
+        assert!(
+            diff.diff
+                .iter()
+                .any(|line| line.line_type() == DiffLineType::Changed)
+        );
+        assert!(
+            diff.to_unified()
+                .contains("+=== 2009–2012: Mozilla years ===")
+        );
+    }
+
     #[tokio::test]
     async fn test_get_bare() {
         let (api, _mock_server) = get_mock_api(
@@ -347,7 +1004,7 @@ mod tests {
             .get_bare(&api, false)
             .await
             .expect("Failed to get page content");
-        assert_eq!(page_info.id, 29414838);
+        assert_eq!(page_info.id, PageId(29414838));
         assert_eq!(
             html_url,
             "https://en.wikipedia.org/w/rest.php/v1/page/Rust%20%28programming%20language%29/html"
@@ -369,6 +1026,23 @@ mod tests {
         assert!(result.contains("<title>Rust (programming language)</title>"));
     }
 
+    #[cfg(feature = "html")]
+    #[tokio::test]
+    async fn test_get_plaintext() {
+        let (api, _mock_server) = get_mock_api(
+            "page_get_html.html",
+            &format!("/page/{}/html", encode("Rust (programming language)")),
+        )
+        .await;
+        let page = Page::new("Rust (programming language)");
+        let plaintext = page
+            .get_plaintext(&api, false, &PlaintextOptions::default())
+            .await
+            .expect("Failed to get plain text");
+        assert_eq!(plaintext.sections[1].heading.as_deref(), Some("History"));
+        assert!(plaintext.text().starts_with("Rust is a general-purpose"));
+    }
+
     #[tokio::test]
     async fn test_get_with_html() {
         let (api, _mock_server) = get_mock_api(
@@ -381,7 +1055,7 @@ mod tests {
             .get_with_html(&api, false, false, HtmlFlavor::View)
             .await
             .expect("Failed to get page content");
-        assert_eq!(page_info.id, 29414838);
+        assert_eq!(page_info.id, PageId(29414838));
         assert!(html.contains("<title>Rust (programming language)</title>"));
     }
 
@@ -452,6 +1126,347 @@ mod tests {
         assert_eq!(history.revisions.len(), 20);
     }
 
+    /// Mounts a history of two segments: `page_history.json`, and one older revision.
+    async fn mount_full_history(mock_server: &MockServer, title: &str) {
+        let history_path = format!("w/rest.php/v1/page/{}/history", encode(title));
+        let older = json!({
+            "revisions": [{
+                "id": 1315000000,
+                "timestamp": "2025-10-01T00:00:00Z",
+                "minor": false,
+                "size": 100,
+                "comment": "",
+                "user": { "id": null, "name": "127.0.0.1" },
+                "delta": 100
+            }],
+            "latest": "https://en.wikipedia.org/w/rest.php/v1/page/Foo/history",
+        });
+        Mock::given(method("GET"))
+            .and(path(&history_path))
+            .and(query_param("older_than", "1315275728"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(older))
+            .mount(mock_server)
+            .await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/page_history.json").expect("Test file missing");
+        let json: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("GET"))
+            .and(path(&history_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_get_full_history() {
+        let title = "Rust (programming language)";
+        let mock_server = MockServer::start().await;
+        mount_full_history(&mock_server, title).await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let page = Page::new(title);
+        let history = page
+            .get_full_history(&api, None, None)
+            .await
+            .expect("Failed to get history");
+        assert_eq!(history.len(), 21);
+        assert_eq!(history[20].id, RevisionId(1315000000));
+        let newer = page
+            .get_full_history(&api, None, Some(RevisionId(1317282086)))
+            .await
+            .expect("Failed to get history");
+        assert_eq!(newer.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_blame() {
+        let title = "Foo";
+        let mock_server = MockServer::start().await;
+        let history = json!({
+            "revisions": [
+                { "id": 3, "timestamp": "2025-10-03T00:00:00Z", "minor": false, "size": 10,
+                  "comment": "", "user": { "id": 3, "name": "Carol" }, "delta": 0 },
+                { "id": 2, "timestamp": "2025-10-02T00:00:00Z", "minor": false, "size": 10,
+                  "comment": "", "user": { "id": 2, "name": "Bob" }, "delta": 5 },
+                { "id": 1, "timestamp": "2025-10-01T00:00:00Z", "minor": false, "size": 5,
+                  "comment": "", "user": { "id": 1, "name": "Alice" }, "delta": 5 }
+            ],
+            "latest": "https://en.wikipedia.org/w/rest.php/v1/page/Foo/history",
+        });
+        // The update only retrieves the revisions newer than the blamed revision
+        let newer = json!({
+            "revisions": [history["revisions"][0].clone()],
+            "latest": "https://en.wikipedia.org/w/rest.php/v1/page/Foo/history",
+        });
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/page/Foo/history"))
+            .and(query_param("newer_than", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(newer))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/page/Foo/history"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(history))
+            .mount(&mock_server)
+            .await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/revision_get.json").expect("Test file missing");
+        let revision: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        for (id, source) in [(1, "Hello"), (2, "Hello\nWorld")] {
+            let mut revision = revision.clone();
+            revision["id"] = json!(id);
+            revision["source"] = json!(source);
+            Mock::given(method("GET"))
+                .and(path(format!("w/rest.php/v1/revision/{id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(revision))
+                .mount(&mock_server)
+                .await;
+        }
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        // Revision 3 is suppressed
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/revision/3"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&mock_server)
+            .await;
+        let blame = Page::new(title)
+            .blame(&api, 2)
+            .await
+            .expect("Failed to compute blame");
+        assert_eq!(blame.revision, RevisionId(2));
+        assert_eq!(blame.skipped, [RevisionId(3)]);
+        assert_eq!(blame.wikitext(), "Hello\nWorld");
+        assert_eq!(
+            blame.line_revision(0).map(|r| r.user.name.as_str()),
+            Some("Alice")
+        );
+        assert_eq!(
+            blame.line_revision(1).map(|r| r.user.name.as_str()),
+            Some("Bob")
+        );
+        let updated = Page::new(title)
+            .update_blame(&api, blame, 2)
+            .await
+            .expect("Failed to update blame");
+        assert_eq!(updated.revision, RevisionId(2));
+        assert_eq!(updated.skipped, [RevisionId(3)]);
+        assert_eq!(updated.wikitext(), "Hello\nWorld");
+        assert_eq!(
+            updated.line_revision(0).map(|r| r.user.name.as_str()),
+            Some("Alice")
+        );
+        assert_eq!(
+            updated.line_revision(1).map(|r| r.user.name.as_str()),
+            Some("Bob")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_revisions_between() {
+        let title = "Foo";
+        let mock_server = MockServer::start().await;
+        // The upper revision ID is used as history cursor
+        let older = json!({
+            "revisions": [{
+                "id": 1318000000,
+                "timestamp": "2025-10-20T00:00:00Z",
+                "minor": false,
+                "size": 100,
+                "comment": "",
+                "user": { "id": null, "name": "127.0.0.1" },
+                "delta": 100
+            }],
+            "latest": "https://en.wikipedia.org/w/rest.php/v1/page/Foo/history",
+        });
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/page/Foo/history"))
+            .and(query_param("older_than", "1318098779"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(older))
+            .mount(&mock_server)
+            .await;
+        mount_full_history(&mock_server, title).await;
+        let bare_text: String =
+            std::fs::read_to_string("test_data/revision_get_bare.json").expect("Test file missing");
+        let mut bare: Value = serde_json::from_str(&bare_text).expect("Failed to parse JSON");
+        // A revision of another page
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/revision/1316925953/bare"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(bare.clone()))
+            .mount(&mock_server)
+            .await;
+        bare["id"] = json!(1318098779);
+        bare["timestamp"] = json!("2025-10-21T22:36:46Z");
+        bare["page"] = json!({ "id": 1, "key": "Foo", "title": "Foo" });
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/revision/1318098779/bare"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(bare))
+            .mount(&mock_server)
+            .await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/revision_get.json").expect("Test file missing");
+        let revision: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("GET"))
+            .and(path_regex(r"^/w/rest.php/v1/revision/\d+$"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(revision))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let page = Page::new(title);
+
+        let empty: Vec<RevisionId> = page
+            .revisions_between(
+                &api,
+                RevisionId(1315275728).into(),
+                "2025-10-01T00:00:00Z"
+                    .try_into()
+                    .expect("Invalid timestamp"),
+                2,
+            )
+            .await
+            .expect("Failed to walk revisions")
+            .map(|(id, _)| id)
+            .collect()
+            .await;
+        assert!(empty.is_empty());
+
+        let results: Vec<_> = page
+            .revisions_between(
+                &api,
+                RevisionId(1315000000).into(),
+                "2025-10-21T23:00:00Z"
+                    .try_into()
+                    .expect("Invalid timestamp"),
+                2,
+            )
+            .await
+            .expect("Failed to walk revisions")
+            .collect()
+            .await;
+        assert_eq!(results.len(), 20);
+        assert_eq!(results[0].0, RevisionId(1315000000));
+        assert_eq!(results[19].0, RevisionId(1318098779));
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        let ids: Vec<RevisionId> = page
+            .revisions_between(&api, RevisionId(1).into(), RevisionId(1318098779).into(), 2)
+            .await
+            .expect("Failed to walk revisions")
+            .map(|(id, _)| id)
+            .collect()
+            .await;
+        assert_eq!(ids, [RevisionId(1318000000), RevisionId(1318098779)]);
+
+        // The upper revision is older than the lower bound
+        let after: Vec<RevisionId> = page
+            .revisions_between(
+                &api,
+                "2025-10-22T00:00:00Z"
+                    .try_into()
+                    .expect("Invalid timestamp"),
+                RevisionId(1318098779).into(),
+                2,
+            )
+            .await
+            .expect("Failed to walk revisions")
+            .map(|(id, _)| id)
+            .collect()
+            .await;
+        assert!(after.is_empty());
+
+        let foreign = page
+            .revisions_between(&api, RevisionId(1).into(), RevisionId(1316925953).into(), 2)
+            .await;
+        assert!(matches!(
+            foreign,
+            Err(RestApiError::RevisionNotOnPage {
+                revision: RevisionId(1316925953),
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fix_lints() {
+        let title = "Rust (programming language)";
+        let (api, mock_server) =
+            get_mock_api("page_get.json", &format!("/page/{}", encode(title))).await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/revision_lint.json").expect("Test file missing");
+        let lints: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/revision/1318205510/lint"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(lints))
+            .mount(&mock_server)
+            .await;
+        let (page_info, patch) = Page::new(title)
+            .fix_lints(&api, &LintFixerRegistry::default())
+            .await
+            .expect("Failed to fix lints");
+        assert_eq!(page_info.latest.id, RevisionId(1318205510));
+        // All lints come from templates
+        assert!(patch.is_empty());
+        assert_eq!(patch.skipped.len(), 14);
+        assert_eq!(patch.wikitext, patch.original);
+    }
+
+    #[tokio::test]
+    async fn test_get_reverts() {
+        let mock_server = MockServer::start().await;
+        let revisions: Vec<Value> = [3, 2, 1]
+            .iter()
+            .map(|id| {
+                json!({ "id": id, "timestamp": "2025-10-01T00:00:00Z", "minor": false, "size": 5,
+                  "comment": "", "user": { "id": id, "name": format!("User{id}") }, "delta": 0 })
+            })
+            .collect();
+        let history = json!({
+            "revisions": revisions,
+            "latest": "https://en.wikipedia.org/w/rest.php/v1/page/Foo/history",
+        });
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/page/Foo/history"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(history))
+            .mount(&mock_server)
+            .await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/revision_get.json").expect("Test file missing");
+        let revision: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        for (id, source) in [(1, "Hello"), (3, "Hello")] {
+            let mut revision = revision.clone();
+            revision["id"] = json!(id);
+            revision["source"] = json!(source);
+            Mock::given(method("GET"))
+                .and(path(format!("w/rest.php/v1/revision/{id}")))
+                .respond_with(ResponseTemplate::new(200).set_body_json(revision))
+                .mount(&mock_server)
+                .await;
+        }
+        // The vandalism in revision 2 is suppressed
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/revision/2"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let reverts = Page::new("Foo")
+            .get_reverts(&api, crate::revert::DEFAULT_REVERT_SEARCH_RADIUS, 2)
+            .await
+            .expect("Failed to detect reverts");
+        assert_eq!(reverts.len(), 1);
+        assert_eq!(reverts[0].reverting, RevisionId(3));
+        assert_eq!(reverts[0].reverting_user.name, "User3");
+        assert_eq!(reverts[0].restored, RevisionId(1));
+        assert_eq!(reverts[0].reverted, [RevisionId(2)]);
+    }
+
     #[tokio::test]
     async fn test_get_history_counts() {
         let (api, _mock_server) = get_mock_api(
@@ -493,8 +1508,8 @@ mod tests {
 
         // Dummy
         let latest = RevisionTimestamp {
-            id: 0,
-            timestamp: String::new(),
+            id: RevisionId::default(),
+            timestamp: Timestamp::default(),
         };
 
         let source = "test123";
@@ -503,10 +1518,283 @@ mod tests {
             .edit(&api, &latest, source, comments)
             .await
             .expect("Failed to edit page");
-        assert_eq!(page_info.id, 81442549);
+        assert_eq!(page_info.id, PageId(81442549));
         assert_eq!(wikitext, source);
     }
 
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_edit_html() {
+        let page_title = "User:Magnus Manske/mediawiki rest api test1";
+        let page = Page::new(page_title);
+        let revision_id = RevisionId(1319097736);
+        let original_html = "<p id=\"mwAg\">test</p>";
+        let html = "<p id=\"mwAg\">test123</p>";
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "w/rest.php/v1/transform/html/to/wikitext/{}/{revision_id}",
+                encode(page_title)
+            )))
+            .and(body_partial_json(json!({
+                "html": html,
+                "original": { "revid": revision_id, "html": { "body": original_html } }
+            })))
+            .and(header("If-Match", "\"1319097736/abc\""))
+            .respond_with(ResponseTemplate::new(200).set_body_string("test123"))
+            .mount(&mock_server)
+            .await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/page_edit.json").expect("Test file missing");
+        let json: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("PUT"))
+            .and(path(format!("w/rest.php/v1/page/{}", encode(page_title))))
+            .and(body_partial_json(json!({ "source": "test123" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&json))
+            .mount(&mock_server)
+            .await;
+
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .with_access_token("foobar")
+            .build();
+        let base_revision = RevisionTimestamp {
+            id: revision_id,
+            timestamp: Timestamp::default(),
+        };
+        let (page_info, wikitext) = page
+            .edit_html(
+                &api,
+                html,
+                &base_revision,
+                original_html,
+                Some("\"1319097736/abc\""),
+                "test edit",
+            )
+            .await
+            .expect("Failed to edit page");
+        assert_eq!(page_info.id, PageId(81442549));
+        assert_eq!(wikitext, "test123");
+    }
+
+    #[tokio::test]
+    async fn test_edit_wikitext() {
+        let title = "Rust (programming language)";
+        let (api, mock_server) =
+            get_mock_api("page_get.json", &format!("/page/{}", encode(title))).await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/page_edit.json").expect("Test file missing");
+        let json: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("PUT"))
+            .and(path(format!("w/rest.php/v1/page/{}", encode(title))))
+            .and(body_partial_json(json!({ "latest": { "id": 1318205510 } })))
+            .and(body_string_contains("[[Category:Test category]]"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&json))
+            .mount(&mock_server)
+            .await;
+
+        let page = Page::new(title);
+        let edited = page
+            .edit_wikitext(&api, "Add category", |editor| {
+                assert!(editor.insert_category("Test category", None));
+            })
+            .await
+            .expect("Failed to edit wikitext");
+        assert!(edited.is_some());
+        let result = page
+            .edit_wikitext(&api, "No change", |editor| {
+                assert_eq!(editor.rename_category("No such category", "Foo"), 0);
+            })
+            .await
+            .expect("Failed to edit wikitext");
+        assert!(result.is_none());
+    }
+
+    #[cfg(feature = "html")]
+    #[tokio::test]
+    async fn test_edit_templates() {
+        let title = "Rust (programming language)";
+        let original_html = r##"<p about="#mwt1" typeof="mw:Transclusion" data-mw='{"parts":[{"template":{"target":{"wt":"Cite web","href":"./Template:Cite_web"},"params":{"accessdate":{"wt":"2020"}},"i":0}}]}' id="mwAg">Foo</p>"##;
+        let (api, mock_server) = get_mock_api(
+            "page_get_bare.json",
+            &format!("/page/{}/bare", encode(title)),
+        )
+        .await;
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/revision/1318205510/html"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(original_html))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "w/rest.php/v1/transform/html/to/wikitext/{}/1318205510",
+                encode(title)
+            )))
+            .and(body_string_contains("access-date"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("test123"))
+            .mount(&mock_server)
+            .await;
+        let test_text: String =
+            std::fs::read_to_string("test_data/page_edit.json").expect("Test file missing");
+        let json: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("PUT"))
+            .and(path(format!("w/rest.php/v1/page/{}", encode(title))))
+            .and(body_partial_json(
+                json!({ "source": "test123", "latest": { "id": 1318205510 } }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&json))
+            .mount(&mock_server)
+            .await;
+
+        let page = Page::new(title);
+        let (_, wikitext) = page
+            .edit_templates(&api, "Cite web", "Rename parameter", |template| {
+                template.params.rename("accessdate", "access-date")
+            })
+            .await
+            .expect("Failed to edit templates")
+            .expect("Page not edited");
+        assert_eq!(wikitext, "test123");
+        let result = page
+            .edit_templates(&api, "Infobox", "Rename parameter", |template| {
+                template.params.rename("accessdate", "access-date")
+            })
+            .await
+            .expect("Failed to edit templates");
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_append_section() {
+        let page_title = "User:Magnus Manske/mediawiki rest api test1";
+        let page = Page::new(page_title);
+        let mock_path = format!("w/rest.php/v1/page/{}", encode(page_title));
+        let mock_server = MockServer::start().await;
+
+        let test_text: String =
+            std::fs::read_to_string("test_data/page_edit.json").expect("Test file missing");
+        let json: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("GET"))
+            .and(path(&mock_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&json))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(&mock_path))
+            .and(body_partial_json(json!({
+                "source": "test123\n\n== Report ==\nAll good.\n",
+                "latest": { "id": 1319097737 }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&json))
+            .mount(&mock_server)
+            .await;
+
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .with_access_token("foobar")
+            .build();
+        let (page_info, _) = page
+            .append_section(&api, "Report", "All good.", "report")
+            .await
+            .expect("Failed to edit page");
+        assert_eq!(page_info.id, PageId(81442549));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_replace_and_insert_section() {
+        let page_title = "User:Magnus Manske/mediawiki rest api test1";
+        let page = Page::new(page_title);
+        let mock_path = format!("w/rest.php/v1/page/{}", encode(page_title));
+        let mock_server = MockServer::start().await;
+
+        let test_text: String =
+            std::fs::read_to_string("test_data/page_edit.json").expect("Test file missing");
+        let json: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        Mock::given(method("GET"))
+            .and(path(&mock_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&json))
+            .mount(&mock_server)
+            .await;
+        for source in ["New lead", "test123\n\n== Report ==\nAll good.\n"] {
+            Mock::given(method("PUT"))
+                .and(path(&mock_path))
+                .and(body_partial_json(json!({
+                    "source": source,
+                    "latest": { "id": 1319097737 }
+                })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(&json))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .with_access_token("foobar")
+            .build();
+        page.replace_section(&api, 0, "New lead", "lead")
+            .await
+            .expect("Failed to replace section");
+        page.insert_section(&api, 1, "Report", "All good.", "report")
+            .await
+            .expect("Failed to insert section");
+        let result = page.replace_section(&api, 3, "Missing", "missing").await;
+        assert!(matches!(result, Err(RestApiError::SectionNotFound(3))));
+    }
+
+    #[tokio::test]
+    #[cfg_attr(miri, ignore)]
+    async fn test_rollback_to() {
+        let title = "Rust (programming language)";
+        let revision_id = RevisionId(1316925953);
+        let read_json = |file: &str| -> Value {
+            let text =
+                std::fs::read_to_string(format!("test_data/{file}")).expect("Test file missing");
+            serde_json::from_str(&text).expect("Failed to parse JSON")
+        };
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("w/rest.php/v1/revision/{revision_id}")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(read_json("revision_get.json")))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("w/rest.php/v1/page/{}", encode(title))))
+            .respond_with(ResponseTemplate::new(200).set_body_json(read_json("page_get.json")))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(format!("w/rest.php/v1/page/{}", encode(title))))
+            .and(body_partial_json(json!({
+                "comment": "Revert to revision 1316925953 by [[Special:Contributions/Paditor|Paditor]]",
+                "latest": { "id": 1318205510 }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(read_json("page_edit.json")))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .with_access_token("foobar")
+            .build();
+
+        let (page_info, _) = Page::new(title)
+            .rollback_to(&api, &Revision::new(revision_id), "")
+            .await
+            .expect("Failed to roll back page");
+        assert_eq!(page_info.id, PageId(81442549));
+
+        let result = Page::new("Cambridge")
+            .rollback_to(&api, &Revision::new(revision_id), "")
+            .await;
+        assert!(matches!(
+            result,
+            Err(RestApiError::RevisionNotOnPage { .. })
+        ));
+    }
+
     #[tokio::test]
     #[cfg_attr(miri, ignore)]
     async fn test_create_enwiki() {
@@ -537,7 +1825,7 @@ mod tests {
             .create(&api, source, comments)
             .await
             .expect("Failed to edit page");
-        assert_eq!(page_info.id, 81447676);
+        assert_eq!(page_info.id, PageId(81447676));
         assert_eq!(wikitext, source);
     }
 }
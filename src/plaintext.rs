@@ -0,0 +1,403 @@
+use crate::html::visible_text;
+use scraper::{CaseSensitivity, ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+
+/// Elements that are removed unless explicitly kept.
+const DEFAULT_EXCLUDE: &[&str] = &[
+    ".shortdescription",
+    ".ambox",
+    ".sidebar",
+    ".metadata",
+    ".noprint",
+    ".mw-empty-elt",
+];
+
+/// Sections that are removed by default, with their subsections.
+const DEFAULT_SKIP_SECTIONS: &[&str] = &[
+    "See also",
+    "Notes",
+    "References",
+    "Citations",
+    "Sources",
+    "Bibliography",
+    "Further reading",
+    "External links",
+];
+
+/// Elements that contain block elements, and are descended into.
+const CONTAINERS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "ul",
+    "ol",
+    "dl",
+    "table",
+    "figure",
+    "blockquote",
+    "pre",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+];
+
+/// Rules for `ParsoidDocument::plaintext`. By default, only prose paragraphs, lists and code are kept.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlaintextOptions {
+    /// Keep reference lists. Reference markers like `[1]` are always removed.
+    pub references: bool,
+    /// Keep infoboxes, as text
+    pub infoboxes: bool,
+    /// Keep navigation boxes, as text
+    pub navboxes: bool,
+    /// Keep hatnotes, like "For other uses, see ..."
+    pub hatnotes: bool,
+    /// Keep tables, as text. Infoboxes and navboxes are kept by their own options.
+    pub tables: bool,
+    /// Keep image captions
+    pub captions: bool,
+    /// CSS selectors of further elements to remove
+    pub exclude: Vec<String>,
+    /// Headings of sections to remove with their subsections, case-insensitive
+    pub skip_sections: Vec<String>,
+}
+
+impl Default for PlaintextOptions {
+    fn default() -> Self {
+        Self {
+            references: false,
+            infoboxes: false,
+            navboxes: false,
+            hatnotes: false,
+            tables: false,
+            captions: false,
+            exclude: DEFAULT_EXCLUDE.iter().map(|s| s.to_string()).collect(),
+            skip_sections: DEFAULT_SKIP_SECTIONS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl PlaintextOptions {
+    /// Adds a CSS selector of elements to remove.
+    pub fn exclude<S: Into<String>>(mut self, selector: S) -> Self {
+        self.exclude.push(selector.into());
+        self
+    }
+
+    /// Adds the heading of a section to remove.
+    pub fn skip_section<S: Into<String>>(mut self, heading: S) -> Self {
+        self.skip_sections.push(heading.into());
+        self
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Returns the selectors of all elements to remove. Invalid custom selectors are ignored.
+    fn selectors(&self) -> Vec<Selector> {
+        let mut selectors: Vec<&str> = self.exclude.iter().map(String::as_str).collect();
+        if !self.references {
+            selectors.extend([".mw-references-wrap", "ol.references", ".reflist"]);
+        }
+        if !self.infoboxes {
+            selectors.push(".infobox");
+        }
+        if !self.navboxes {
+            selectors.extend([".navbox", ".vertical-navbox", "[role=\"navigation\"]"]);
+        }
+        if !self.hatnotes {
+            selectors.extend([".hatnote", ".dablink", ".rellink"]);
+        }
+        if !self.captions {
+            selectors.extend(["figcaption", ".thumbcaption", ".gallerytext"]);
+        }
+        selectors
+            .into_iter()
+            .filter_map(|selector| Selector::parse(selector).ok())
+            .collect()
+    }
+}
+
+/// A section of plain text.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaintextSection {
+    /// The heading, or `None` for the lead section
+    pub heading: Option<String>,
+    /// The heading level, eg 2 for `== History ==`, or 0 for the lead section
+    pub level: usize,
+    /// Paragraphs, lists and code blocks, with lines separated by `\n`
+    pub paragraphs: Vec<String>,
+}
+
+/// The prose of a page, by section.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Plaintext {
+    pub sections: Vec<PlaintextSection>,
+}
+
+impl Plaintext {
+    /// Extracts the plain text from a Parsoid HTML document.
+    pub(crate) fn from_html(document: &Html, options: &PlaintextOptions) -> Self {
+        let mut extractor = Extractor {
+            selectors: options.selectors(),
+            tables: options.tables,
+            skip_sections: options
+                .skip_sections
+                .iter()
+                .map(|heading| heading.trim().to_lowercase())
+                .collect(),
+            sections: vec![PlaintextSection::default()],
+        };
+        let root = document.root_element();
+        let body = root
+            .child_elements()
+            .find(|child| child.value().name() == "body")
+            .unwrap_or(root);
+        extractor.walk(&body);
+        let mut sections = extractor.sections;
+        sections.retain(|section| section.heading.is_some() || !section.paragraphs.is_empty());
+        Self { sections }
+    }
+
+    /// Returns all paragraphs, in document order.
+    pub fn paragraphs(&self) -> impl Iterator<Item = &str> {
+        self.sections
+            .iter()
+            .flat_map(|section| section.paragraphs.iter().map(String::as_str))
+    }
+
+    /// Returns the text, with headings on their own line and paragraphs separated by empty lines.
+    pub fn text(&self) -> String {
+        let mut blocks: Vec<&str> = vec![];
+        for section in &self.sections {
+            if let Some(heading) = &section.heading {
+                blocks.push(heading);
+            }
+            blocks.extend(section.paragraphs.iter().map(String::as_str));
+        }
+        blocks.join("\n\n")
+    }
+}
+
+/// Walks a document, collecting sections and paragraphs.
+struct Extractor {
+    selectors: Vec<Selector>,
+    tables: bool,
+    skip_sections: Vec<String>,
+    sections: Vec<PlaintextSection>,
+}
+
+impl Extractor {
+    fn walk(&mut self, element: &ElementRef) {
+        for child in element.child_elements() {
+            if self
+                .selectors
+                .iter()
+                .any(|selector| selector.matches(&child))
+            {
+                continue;
+            }
+            match child.value().name() {
+                "section" => {
+                    if !self.is_skipped_section(&child) {
+                        self.walk(&child);
+                    }
+                }
+                name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                    self.sections.push(PlaintextSection {
+                        heading: Some(visible_text(&child)),
+                        level: name[1..].parse().unwrap_or_default(),
+                        paragraphs: vec![],
+                    });
+                }
+                "table" => {
+                    if self.tables || Self::is_box(&child) {
+                        self.push_paragraph(&child);
+                    }
+                }
+                "p" | "ul" | "ol" | "dl" | "pre" | "blockquote" | "figcaption" => {
+                    self.push_paragraph(&child);
+                }
+                "style" | "script" | "link" | "meta" => {}
+                _ => {
+                    if Self::has_blocks(&child) {
+                        self.walk(&child);
+                    } else {
+                        self.push_paragraph(&child);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks if a section starts with a heading that is to be skipped.
+    fn is_skipped_section(&self, section: &ElementRef) -> bool {
+        section
+            .child_elements()
+            .next()
+            .filter(|child| {
+                matches!(
+                    child.value().name(),
+                    "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+                )
+            })
+            .is_some_and(|heading| {
+                let heading = visible_text(&heading).to_lowercase();
+                self.skip_sections.contains(&heading)
+            })
+    }
+
+    /// Checks if a table is an infobox or navbox, which are not subject to the `tables` option.
+    fn is_box(table: &ElementRef) -> bool {
+        ["infobox", "navbox", "vertical-navbox"]
+            .iter()
+            .any(|class| {
+                table
+                    .value()
+                    .has_class(class, CaseSensitivity::CaseSensitive)
+            })
+    }
+
+    fn has_blocks(element: &ElementRef) -> bool {
+        element
+            .child_elements()
+            .any(|child| CONTAINERS.contains(&child.value().name()))
+    }
+
+    fn push_paragraph(&mut self, element: &ElementRef) {
+        let text = self.visible_text_filtered(element);
+        if text.is_empty() {
+            return;
+        }
+        if let Some(section) = self.sections.last_mut() {
+            section.paragraphs.push(text);
+        }
+    }
+
+    /// Returns the visible text of an element, without excluded descendants.
+    fn visible_text_filtered(&self, element: &ElementRef) -> String {
+        let has_excluded = element
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .skip(1)
+            .any(|child| {
+                self.selectors
+                    .iter()
+                    .any(|selector| selector.matches(&child))
+            });
+        if !has_excluded {
+            return visible_text(element);
+        }
+        let mut html = Html::parse_fragment(&element.html());
+        let excluded: Vec<_> = html
+            .root_element()
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .skip(1)
+            .filter(|child| {
+                self.selectors
+                    .iter()
+                    .any(|selector| selector.matches(child))
+            })
+            .map(|child| child.id())
+            .collect();
+        for id in excluded {
+            if let Some(mut node) = html.tree.get_mut(id) {
+                node.detach();
+            }
+        }
+        visible_text(&html.root_element())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html::ParsoidDocument;
+
+    fn test_document() -> ParsoidDocument {
+        let html =
+            std::fs::read_to_string("test_data/page_get_html.html").expect("Test file missing");
+        ParsoidDocument::parse(&html)
+    }
+
+    #[test]
+    fn test_plaintext_defaults() {
+        let plaintext = test_document().plaintext(&PlaintextOptions::default());
+        let lead = &plaintext.sections[0];
+        assert_eq!(lead.heading, None);
+        assert_eq!(lead.level, 0);
+        assert_eq!(lead.paragraphs.len(), 4);
+        assert!(
+            lead.paragraphs[0]
+                .starts_with("Rust is a general-purpose programming language. It is noted")
+        );
+        let history = &plaintext.sections[1];
+        assert_eq!(history.heading.as_deref(), Some("History"));
+        assert_eq!(history.level, 2);
+        assert_eq!(plaintext.sections[2].level, 3);
+        assert!(
+            plaintext
+                .sections
+                .iter()
+                .all(|section| section.heading.as_deref() != Some("References"))
+        );
+        let text = plaintext.text();
+        assert!(text.starts_with("Rust is a general-purpose programming language."));
+        assert!(text.contains("\n\nHistory\n\n2006–2009: Early years\n\nRust began as"));
+        assert!(!text.contains("[1]"));
+        assert!(!text.contains("Paradigms"));
+        assert!(!text.contains("Retrieved"));
+        assert_eq!(plaintext.paragraphs().count(), 140);
+    }
+
+    #[test]
+    fn test_plaintext_options() {
+        let document = test_document();
+        let options = PlaintextOptions {
+            infoboxes: true,
+            skip_sections: vec![],
+            ..Default::default()
+        }
+        .exclude("pre")
+        .skip_section("history");
+        let plaintext = document.plaintext(&options);
+        assert!(plaintext.sections[0].paragraphs[0].starts_with("Rust\n"));
+        assert!(plaintext.paragraphs().any(|p| p.contains("Paradigms")));
+        let headings: Vec<&str> = plaintext
+            .sections
+            .iter()
+            .filter_map(|section| section.heading.as_deref())
+            .collect();
+        assert!(headings.contains(&"References"));
+        assert!(!headings.contains(&"History"));
+        assert!(!headings.contains(&"2006–2009: Early years"));
+        assert!(!plaintext.text().contains("fn main()"));
+    }
+
+    #[test]
+    fn test_plaintext_rules() {
+        let html = r#"<body><section data-mw-section-id="0"><div role="note" class="hatnote">For other uses, see X.</div>
+            <p>Lead <b>text</b><sup class="mw-ref reference"><a>[1]</a></sup>.</p>
+            <figure typeof="mw:File/Thumb"><a><img/></a><figcaption>A caption</figcaption></figure>
+            <div class="custom">Custom <span class="drop">dropped</span> text</div></section></body>"#;
+        let document = ParsoidDocument::parse(html);
+        let plaintext = document.plaintext(&PlaintextOptions::default().exclude(".drop"));
+        assert_eq!(plaintext.text(), "Lead text.\n\nCustom text");
+        let options = PlaintextOptions {
+            hatnotes: true,
+            captions: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            document.plaintext(&options).text(),
+            "For other uses, see X.\n\nLead text.\n\nA caption\n\nCustom dropped text"
+        );
+    }
+}
@@ -1,9 +1,30 @@
+pub use crate::blame::{Blame, BlameLine, BlameRevision, BlameSpan};
+pub use crate::citation::{Citation, CitationTemplate};
+pub use crate::diff::DiffSegment;
 pub use crate::error::RestApiError;
 pub use crate::file::File;
+#[cfg(feature = "html")]
+pub use crate::html::{
+    ExternalLink, HtmlSection, Media, ParsoidDocument, Reference, TemplateCall, TemplateParam,
+    TemplateParams, Transclusion, WikiLink,
+};
+pub use crate::id::{ItemId, PageId, RevisionId, UserId};
+pub use crate::lint::{Lint, LintCategory, LintParams, LintSeverity, SourceRange, TemplateInfo};
+pub use crate::lint_fix::{LintFix, LintFixer, LintFixerRegistry, LintPatch, LintSuggestion};
+pub use crate::lint_report::{LintCount, LintReport};
 pub use crate::math::Math;
 pub use crate::page::Page;
+#[cfg(feature = "html")]
+pub use crate::plaintext::{Plaintext, PlaintextOptions, PlaintextSection};
 pub use crate::rest_api::RestApi;
+pub use crate::revert::{Revert, RevertDetector};
 pub use crate::revision::Revision;
 pub use crate::search::Search;
-pub use crate::transform::Transform;
+pub use crate::section::{WikitextSection, WikitextSections};
+#[cfg(feature = "html")]
+pub use crate::table::{Infobox, Table};
+pub use crate::timestamp::Timestamp;
+pub use crate::transform::{Transform, TransformCache, TransformOptions};
 pub use crate::utilities::*;
+pub use crate::wikitext::{Span, Wikitext, WikitextNode};
+pub use crate::wikitext_edit::{WikitextEdit, WikitextEditor};
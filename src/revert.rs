@@ -0,0 +1,157 @@
+use crate::prelude::{HistoryRevisionInfo, RevisionId, UserInfo, content_hash};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// The default number of earlier revisions that are searched for a matching content hash,
+/// the same as `MediaWiki`'s `$wgManualRevertSearchRadius`
+pub const DEFAULT_REVERT_SEARCH_RADIUS: usize = 15;
+
+/// An identity revert: a revision that restored the exact content of an earlier revision.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Revert {
+    /// The reverting revision
+    pub reverting: RevisionId,
+    pub reverting_user: UserInfo,
+    /// The earlier revision whose content was restored
+    pub restored: RevisionId,
+    /// The reverted revisions between the restored and the reverting revision, oldest first
+    pub reverted: Vec<RevisionId>,
+}
+
+/// Detects identity reverts in a page history, following `MediaWiki`'s manual revert detection:
+/// a revision is a revert if its content hash matches one of the previous `search_radius` revisions,
+/// and all revisions in between are reverted.
+/// A revision with the same content as its direct predecessor (eg a page protection) is not a revert.
+#[derive(Clone, Debug)]
+pub struct RevertDetector {
+    search_radius: usize,
+    /// The most recent revision IDs and content hashes, oldest first.
+    /// The hash is `None` for revisions whose content is not available.
+    recent: VecDeque<(RevisionId, Option<String>)>,
+}
+
+impl Default for RevertDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_REVERT_SEARCH_RADIUS)
+    }
+}
+
+impl RevertDetector {
+    /// Creates a new detector, searching up to `search_radius` earlier revisions for a matching content hash.
+    pub const fn new(search_radius: usize) -> Self {
+        Self {
+            search_radius,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Adds the next revision of the page (oldest first), and returns the revert it constitutes, if any.
+    pub fn push(&mut self, revision: &HistoryRevisionInfo, wikitext: &str) -> Option<Revert> {
+        self.push_hash(revision, content_hash(wikitext))
+    }
+
+    /// Adds the next revision of the page (oldest first), using a precomputed content hash,
+    /// and returns the revert it constitutes, if any.
+    pub fn push_hash(&mut self, revision: &HistoryRevisionInfo, hash: String) -> Option<Revert> {
+        let restored_pos = self
+            .recent
+            .iter()
+            .rposition(|(_, recent_hash)| recent_hash.as_ref() == Some(&hash));
+        let ret = restored_pos
+            .filter(|pos| pos + 1 < self.recent.len())
+            .map(|pos| Revert {
+                reverting: revision.id,
+                reverting_user: revision.user.to_owned(),
+                restored: self.recent[pos].0,
+                reverted: self
+                    .recent
+                    .iter()
+                    .skip(pos + 1)
+                    .map(|(id, _)| *id)
+                    .collect(),
+            });
+        self.push_recent(revision.id, Some(hash));
+        ret
+    }
+
+    /// Adds the next revision of the page (oldest first) whose content is not available, eg a suppressed revision.
+    /// It can not be a revert or be restored, but is reported as reverted if a later revision restores an earlier one.
+    pub fn push_unavailable(&mut self, revision: &HistoryRevisionInfo) {
+        self.push_recent(revision.id, None);
+    }
+
+    /// Detects all reverts in a sequence of revisions and their wikitext, oldest first.
+    pub fn detect<'a, I>(&mut self, revisions: I) -> Vec<Revert>
+    where
+        I: IntoIterator<Item = (&'a HistoryRevisionInfo, &'a str)>,
+    {
+        revisions
+            .into_iter()
+            .filter_map(|(revision, wikitext)| self.push(revision, wikitext))
+            .collect()
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    fn push_recent(&mut self, id: RevisionId, hash: Option<String>) {
+        self.recent.push_back((id, hash));
+        while self.recent.len() > self.search_radius {
+            self.recent.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn revision(id: usize) -> HistoryRevisionInfo {
+        serde_json::from_value(json!({
+            "id": id,
+            "size": 0,
+            "delta": 0,
+            "comment": "",
+            "minor": false,
+            "timestamp": "2025-10-15T07:59:08Z",
+            "user": { "id": id, "name": format!("User{id}") }
+        }))
+        .expect("Failed to parse revision")
+    }
+
+    fn detect(search_radius: usize, texts: &[&str]) -> Vec<Revert> {
+        let revisions: Vec<_> = (1..=texts.len()).map(revision).collect();
+        RevertDetector::new(search_radius).detect(revisions.iter().zip(texts.iter().copied()))
+    }
+
+    #[test]
+    fn test_detect() {
+        let reverts = detect(15, &["A", "B", "C", "A", "A", "D", "A"]);
+        assert_eq!(reverts.len(), 2);
+        assert_eq!(reverts[0].reverting, RevisionId(4));
+        assert_eq!(reverts[0].reverting_user.name, "User4");
+        assert_eq!(reverts[0].restored, RevisionId(1));
+        assert_eq!(reverts[0].reverted, [RevisionId(2), RevisionId(3)]);
+        // Revision 5 is identical to revision 4, and is no revert
+        assert_eq!(reverts[1].reverting, RevisionId(7));
+        assert_eq!(reverts[1].restored, RevisionId(5));
+        assert_eq!(reverts[1].reverted, [RevisionId(6)]);
+    }
+
+    #[test]
+    fn test_detect_search_radius() {
+        assert_eq!(detect(3, &["A", "B", "C", "A"]).len(), 1);
+        assert!(detect(2, &["A", "B", "C", "A"]).is_empty());
+    }
+
+    #[test]
+    fn test_push_unavailable() {
+        let mut detector = RevertDetector::default();
+        assert!(detector.push(&revision(1), "A").is_none());
+        detector.push_unavailable(&revision(2));
+        let revert = detector.push(&revision(3), "A").expect("Revert expected");
+        assert_eq!(revert.restored, RevisionId(1));
+        assert_eq!(revert.reverted, [RevisionId(2)]);
+    }
+}
@@ -1,21 +1,24 @@
+#[cfg(feature = "html")]
+use crate::prelude::{ParsoidDocument, Plaintext, PlaintextOptions};
 use crate::{
+    bulk::{BulkStream, run_concurrent},
     error::RestApiError,
-    prelude::{Diff, HtmlFlavor, Lint, RestApi, RevisionInfo},
+    prelude::{Diff, HtmlFlavor, Lint, Page, PageInfo, RestApi, RevisionId, RevisionInfo},
 };
 use serde_json::{Value, from_value};
 use std::collections::HashMap;
 
 #[derive(Clone, Copy, Debug)]
 pub struct Revision {
-    id: usize,
+    id: RevisionId,
 }
 
 impl Revision {
-    pub const fn new(id: usize) -> Self {
+    pub const fn new(id: RevisionId) -> Self {
         Self { id }
     }
 
-    pub const fn id(&self) -> usize {
+    pub const fn id(&self) -> RevisionId {
         self.id
     }
 
@@ -37,6 +40,58 @@ impl Revision {
         Ok((ret, wikitext))
     }
 
+    /// Retrieves basic revision information and wikitext for many revisions,
+    /// with at most `concurrency` requests running in parallel.
+    /// Yields `(revision_id, result)` pairs in completion order, or in input order if `preserve_order` is set.
+    /// Errors are reported per revision and do not abort the batch.
+    pub fn get_many<I: IntoIterator<Item = RevisionId>>(
+        api: &RestApi,
+        ids: I,
+        concurrency: usize,
+        preserve_order: bool,
+    ) -> BulkStream<'_, RevisionId, (RevisionInfo, String)> {
+        let ids = ids.into_iter().collect();
+        run_concurrent(ids, concurrency, preserve_order, move |id| async move {
+            Revision::new(id).get(api).await
+        })
+    }
+
+    /// Undoes this revision, by restoring the content of its parent revision.
+    /// This revision must be the latest revision of its page, otherwise `RestApiError::NotLatestRevision` is returned.
+    /// The edit is based on the latest revision, so a concurrent edit results in an edit conflict rather than being overwritten.
+    /// The edit summary follows the `MediaWiki` undo format, with `comment` appended if it is not empty.
+    pub async fn undo(
+        &self,
+        api: &RestApi,
+        comment: &str,
+    ) -> Result<(PageInfo, String), RestApiError> {
+        let (revision_info, _) = self.get_bare(api).await?;
+        let page = Page::new(&revision_info.page.title);
+        let (page_info, _) = page.get(api, false).await?;
+        if page_info.latest.id != self.id {
+            return Err(RestApiError::NotLatestRevision {
+                revision: self.id,
+                latest: page_info.latest.id,
+            });
+        }
+        let history = page.get_history(api, None, Some(self.id), None).await?;
+        let parent = history
+            .revisions
+            .first()
+            .ok_or(RestApiError::NoParentRevision(self.id))?;
+        let (_, parent_wikitext) = Revision::new(parent.id).get(api).await?;
+        let user = &revision_info.user.name;
+        let summary = Self::revert_summary(
+            &format!(
+                "Undo revision {} by [[Special:Contributions/{user}|{user}]]",
+                self.id
+            ),
+            comment,
+        );
+        page.edit(api, &page_info.latest, &parent_wikitext, &summary)
+            .await
+    }
+
     /// Retrieves basic revision information and the link to the HTML.
     pub async fn get_bare(&self, api: &RestApi) -> Result<(RevisionInfo, String), RestApiError> {
         let path = format!("/revision/{}/bare", self.id);
@@ -62,6 +117,30 @@ impl Revision {
         stash: bool,
         flavor: HtmlFlavor,
     ) -> Result<String, RestApiError> {
+        let (html, _etag) = self.get_html_with_etag(api, stash, flavor).await?;
+        Ok(html)
+    }
+
+    /// Retrieves the prose of the revision by section, from its Parsoid HTML,
+    /// without the elements and sections removed by `options`.
+    #[cfg(feature = "html")]
+    pub async fn get_plaintext(
+        &self,
+        api: &RestApi,
+        options: &PlaintextOptions,
+    ) -> Result<Plaintext, RestApiError> {
+        let html = self.get_html(api, false, HtmlFlavor::View).await?;
+        Ok(ParsoidDocument::parse(&html).plaintext(options))
+    }
+
+    /// Retrieves the HTML for the revision, and the `ETag` it was served with (if any).
+    /// The `ETag` identifies the render, and can be passed on for selective serialization.
+    pub async fn get_html_with_etag(
+        &self,
+        api: &RestApi,
+        stash: bool,
+        flavor: HtmlFlavor,
+    ) -> Result<(String, Option<String>), RestApiError> {
         let path = format!("/revision/{}/html", self.id);
         let mut params = HashMap::new();
         params.insert("stash".to_string(), stash.to_string());
@@ -71,8 +150,13 @@ impl Revision {
             .await?
             .build()?;
         let response = api.execute(request).await?;
-        let ret = response.text().await?;
-        Ok(ret)
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| etag.to_string());
+        let html = response.text().await?;
+        Ok((html, etag))
     }
 
     /// Retrieves basic revision information and the HTML for the revision.
@@ -113,7 +197,7 @@ impl Revision {
         Ok(ret)
     }
 
-    pub async fn get_compare(&self, api: &RestApi, to: usize) -> Result<Diff, RestApiError> {
+    pub async fn get_compare(&self, api: &RestApi, to: RevisionId) -> Result<Diff, RestApiError> {
         let path = format!("/revision/{}/compare/{to}", self.id);
         let params = HashMap::new();
         let request = api
@@ -124,16 +208,33 @@ impl Revision {
         let ret: Diff = response.json().await?;
         Ok(ret)
     }
+
+    // ____________________________________________________________________________________________________
+    // Crate-public functions
+
+    /// Builds an edit summary for reverting edits, with an optional user comment.
+    pub(crate) fn revert_summary(summary: &str, comment: &str) -> String {
+        let comment = comment.trim();
+        if comment.is_empty() {
+            summary.to_string()
+        } else {
+            format!("{summary}: {comment}")
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path};
+    use crate::prelude::PageId;
+    use futures::StreamExt;
+    use serde_json::json;
+    use urlencoding::encode;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    const TEST_REVISION_ID: usize = 1316925953;
-    const TEST_REVISION_OLD_ID: usize = 1316608902;
+    const TEST_REVISION_ID: RevisionId = RevisionId(1316925953);
+    const TEST_REVISION_OLD_ID: RevisionId = RevisionId(1316608902);
 
     async fn get_mock_api(test_file: &str, test_path: &str) -> (RestApi, MockServer) {
         let mock_path = format!("w/rest.php/v1{}", test_path.replace(' ', "%20"));
@@ -178,6 +279,23 @@ mod tests {
         assert!(wikitext.contains("[[FreeBSD]]"));
     }
 
+    #[tokio::test]
+    async fn test_get_many() {
+        let (api, _mock_server) = get_mock_api(
+            "revision_get.json",
+            &format!("/revision/{TEST_REVISION_ID}"),
+        )
+        .await;
+        let ids = [TEST_REVISION_ID, TEST_REVISION_OLD_ID];
+        let results: Vec<_> = Revision::get_many(&api, ids, 2, true).collect().await;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, TEST_REVISION_ID);
+        let (revision_info, _) = results[0].1.as_ref().expect("Failed to get revision");
+        assert_eq!(revision_info.size, 114334);
+        assert_eq!(results[1].0, TEST_REVISION_OLD_ID);
+        assert!(results[1].1.is_err());
+    }
+
     #[tokio::test]
     async fn test_get_html() {
         let (api, _mock_server) = get_mock_api(
@@ -193,6 +311,34 @@ mod tests {
         assert!(html.contains("<title>Rust (programming language)</title>"));
     }
 
+    #[tokio::test]
+    async fn test_get_html_with_etag() {
+        let html: String =
+            std::fs::read_to_string("test_data/revision_get_html.html").expect("Test file missing");
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "w/rest.php/v1/revision/{TEST_REVISION_ID}/html"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(&html)
+                    .insert_header("ETag", "\"1316925953/abc\""),
+            )
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let revision = Revision::new(TEST_REVISION_ID);
+        let (result, etag) = revision
+            .get_html_with_etag(&api, true, HtmlFlavor::Edit)
+            .await
+            .expect("Failed to get page content");
+        assert_eq!(result, html);
+        assert_eq!(etag.as_deref(), Some("\"1316925953/abc\""));
+    }
+
     #[tokio::test]
     async fn test_get_with_html() {
         let (api, _mock_server) = get_mock_api(
@@ -245,6 +391,100 @@ mod tests {
         assert_eq!(result.to.sections.len(), 52);
     }
 
+    /// Mounts the mocks for undoing `TEST_REVISION_ID`, with the page at `latest`.
+    async fn mount_undo_mocks(mock_server: &MockServer, latest: RevisionId) {
+        let title = "Rust (programming language)";
+        let read_json = |file: &str| -> Value {
+            let text =
+                std::fs::read_to_string(format!("test_data/{file}")).expect("Test file missing");
+            serde_json::from_str(&text).expect("Failed to parse JSON")
+        };
+        let mut page = read_json("page_get.json");
+        page["latest"]["id"] = json!(latest);
+        let mut parent = read_json("revision_get.json");
+        parent["id"] = json!(1318205510);
+        parent["source"] = json!("parent text");
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "w/rest.php/v1/revision/{TEST_REVISION_ID}/bare"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(read_json("revision_get_bare.json")),
+            )
+            .mount(mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("w/rest.php/v1/page/{}", encode(title))))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page))
+            .mount(mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "w/rest.php/v1/page/{}/history",
+                encode(title)
+            )))
+            .and(query_param("older_than", TEST_REVISION_ID.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(read_json("page_history.json")))
+            .mount(mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("w/rest.php/v1/revision/1318205510"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(parent))
+            .mount(mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(format!("w/rest.php/v1/page/{}", encode(title))))
+            .and(body_partial_json(json!({
+                "source": "parent text",
+                "comment": "Undo revision 1316925953 by [[Special:Contributions/Paditor|Paditor]]: vandalism",
+                "latest": { "id": latest }
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(read_json("page_edit.json")))
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_undo() {
+        let mock_server = MockServer::start().await;
+        mount_undo_mocks(&mock_server, TEST_REVISION_ID).await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .with_access_token("foobar")
+            .build();
+        let (page_info, _) = Revision::new(TEST_REVISION_ID)
+            .undo(&api, "vandalism")
+            .await
+            .expect("Failed to undo revision");
+        assert_eq!(page_info.id, PageId(81442549));
+    }
+
+    #[tokio::test]
+    async fn test_undo_not_latest() {
+        let mock_server = MockServer::start().await;
+        mount_undo_mocks(&mock_server, RevisionId(1318205510)).await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .with_access_token("foobar")
+            .build();
+        let result = Revision::new(TEST_REVISION_ID)
+            .undo(&api, "vandalism")
+            .await;
+        assert!(matches!(
+            result,
+            Err(RestApiError::NotLatestRevision {
+                revision: TEST_REVISION_ID,
+                latest: RevisionId(1318205510)
+            })
+        ));
+    }
+
+    #[test]
+    fn test_revert_summary() {
+        assert_eq!(Revision::revert_summary("Undo", " "), "Undo");
+        assert_eq!(Revision::revert_summary("Undo", "spam"), "Undo: spam");
+    }
+
     #[tokio::test]
     async fn test_get_lint() {
         let (api, _mock_server) = get_mock_api(
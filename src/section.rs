@@ -0,0 +1,428 @@
+use crate::error::RestApiError;
+
+/// Tags whose contents are not parsed for headings.
+pub(crate) const OPAQUE_TAGS: &[&str] = &[
+    "nowiki",
+    "pre",
+    "syntaxhighlight",
+    "source",
+    "math",
+    "chem",
+    "score",
+    "graph",
+    "templatedata",
+];
+
+/// A headed section of wikitext.
+/// `heading`, `level` and `offset` match the fields of `DiffSection`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WikitextSection {
+    /// The heading line as it appears in the wikitext, eg "== History =="
+    pub heading: String,
+    /// The heading text without the "=" markup, eg "History"
+    pub title: String,
+    pub level: usize,
+    /// The anchor of the section, eg "`Early_years`"
+    pub anchor: String,
+    /// Byte offset of the heading in the wikitext
+    pub offset: usize,
+    /// Byte offset where the section ends, including all its subsections
+    pub end: usize,
+}
+
+/// Wikitext split into sections, as `MediaWiki` numbers them:
+/// section 0 is the lead before the first heading, section 1 the first headed section, and so on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WikitextSections {
+    wikitext: String,
+    sections: Vec<WikitextSection>,
+}
+
+impl WikitextSections {
+    /// Parses wikitext into sections.
+    /// Headings inside comments, templates, `<nowiki>`, `<pre>` and similar tags are ignored.
+    pub fn parse<S: Into<String>>(wikitext: S) -> Self {
+        let wikitext = wikitext.into();
+        let sections = Self::find_sections(&wikitext);
+        Self { wikitext, sections }
+    }
+
+    /// Returns the complete wikitext.
+    pub fn wikitext(&self) -> &str {
+        &self.wikitext
+    }
+
+    /// Returns the complete wikitext, consuming the object.
+    pub fn into_wikitext(self) -> String {
+        self.wikitext
+    }
+
+    /// Returns the headed sections. Note that `sections()[0]` is section number 1.
+    pub fn sections(&self) -> &[WikitextSection] {
+        &self.sections
+    }
+
+    /// Returns the section with the given number (1-based), if it exists.
+    pub fn section(&self, number: usize) -> Option<&WikitextSection> {
+        self.sections.get(number.checked_sub(1)?)
+    }
+
+    /// Returns the number of the first section with the given title, if any.
+    pub fn find(&self, title: &str) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|section| section.title == title)
+            .map(|pos| pos + 1)
+    }
+
+    /// Returns the wikitext of a section, including its heading and subsections.
+    /// Section 0 is the lead.
+    pub fn section_text(&self, number: usize) -> Option<&str> {
+        let (start, end) = self.section_range(number)?;
+        self.wikitext.get(start..end)
+    }
+
+    /// Replaces a section, including its heading and subsections, with new wikitext.
+    /// Section 0 is the lead.
+    pub fn replace_section(&mut self, number: usize, text: &str) -> Result<(), RestApiError> {
+        let (start, end) = self
+            .section_range(number)
+            .ok_or(RestApiError::SectionNotFound(number))?;
+        let mut text = text.to_string();
+        if end < self.wikitext.len() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        self.splice(start, end, &text);
+        Ok(())
+    }
+
+    /// Removes a section, including its heading and subsections.
+    pub fn remove_section(&mut self, number: usize) -> Result<(), RestApiError> {
+        if number == 0 {
+            return Err(RestApiError::SectionNotFound(number));
+        }
+        self.replace_section(number, "")
+    }
+
+    /// Appends a new section at the end of the wikitext.
+    pub fn append_section(&mut self, title: &str, level: usize, body: &str) {
+        let mut wikitext = self.wikitext.trim_end().to_string();
+        if !wikitext.is_empty() {
+            wikitext += "\n\n";
+        }
+        wikitext += &Self::format_section(title, level, body);
+        self.wikitext = wikitext;
+        self.sections = Self::find_sections(&self.wikitext);
+    }
+
+    /// Inserts a new section before the section with the given number (1-based).
+    /// Inserting before the section after the last one appends the section.
+    pub fn insert_section(
+        &mut self,
+        number: usize,
+        title: &str,
+        level: usize,
+        body: &str,
+    ) -> Result<(), RestApiError> {
+        if number == self.sections.len() + 1 {
+            self.append_section(title, level, body);
+            return Ok(());
+        }
+        let offset = self
+            .section(number)
+            .ok_or(RestApiError::SectionNotFound(number))?
+            .offset;
+        let text = format!("{}\n", Self::format_section(title, level, body));
+        self.splice(offset, offset, &text);
+        Ok(())
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Returns the start and end byte offsets of a section. Section 0 is the lead.
+    fn section_range(&self, number: usize) -> Option<(usize, usize)> {
+        if number == 0 {
+            let end = self
+                .sections
+                .first()
+                .map_or(self.wikitext.len(), |section| section.offset);
+            return Some((0, end));
+        }
+        self.section(number)
+            .map(|section| (section.offset, section.end))
+    }
+
+    /// Replaces a byte range of the wikitext, and re-parses the sections.
+    fn splice(&mut self, start: usize, end: usize, text: &str) {
+        self.wikitext.replace_range(start..end, text);
+        self.sections = Self::find_sections(&self.wikitext);
+    }
+
+    /// Formats a new section with heading and body.
+    fn format_section(title: &str, level: usize, body: &str) -> String {
+        let markup = "=".repeat(level.clamp(1, 6));
+        format!("{markup} {} {markup}\n{}\n", title.trim(), body.trim_end())
+    }
+
+    /// Finds all headed sections in the wikitext.
+    fn find_sections(wikitext: &str) -> Vec<WikitextSection> {
+        let bytes = wikitext.as_bytes();
+        let lowercase = wikitext.to_ascii_lowercase();
+        let mut sections: Vec<WikitextSection> = vec![];
+        let mut template_depth = 0;
+        let mut line_start = true;
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let rest = &wikitext[pos..];
+            if line_start && template_depth == 0 && bytes[pos] == b'=' {
+                let line_end = rest.find('\n').map_or(bytes.len(), |end| pos + end);
+                if let Some(section) = Self::parse_heading(wikitext, pos, line_end) {
+                    sections.push(section);
+                    pos = line_end;
+                    continue;
+                }
+            }
+            line_start = false;
+            if rest.starts_with("<!--") {
+                pos = rest.find("-->").map_or(bytes.len(), |end| pos + end + 3);
+            } else if rest.starts_with("{{") {
+                template_depth += 1;
+                pos += 2;
+            } else if rest.starts_with("}}") && template_depth > 0 {
+                template_depth -= 1;
+                pos += 2;
+            } else if bytes[pos] == b'<' {
+                pos = Self::skip_opaque_tag(&lowercase, pos);
+            } else {
+                line_start = bytes[pos] == b'\n';
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        Self::set_section_ends(&mut sections, bytes.len());
+        sections
+    }
+
+    /// If there is an opaque tag (eg `<nowiki>`) at `pos`, returns the position after its closing tag.
+    /// Otherwise, returns the position after the "<".
+    fn skip_opaque_tag(lowercase: &str, pos: usize) -> usize {
+        let rest = &lowercase[pos + 1..];
+        for tag in OPAQUE_TAGS {
+            let Some(after_name) = rest.strip_prefix(tag) else {
+                continue;
+            };
+            if !after_name.starts_with(['>', '/', ' ', '\t', '\n']) {
+                continue;
+            }
+            let Some(tag_end) = after_name.find('>') else {
+                continue;
+            };
+            if after_name[..tag_end].ends_with('/') {
+                // Self-closing, eg <nowiki/>
+                return pos + 1 + tag.len() + tag_end + 1;
+            }
+            let content_start = pos + 1 + tag.len() + tag_end + 1;
+            let closing = format!("</{tag}");
+            let Some(close) = lowercase[content_start..].find(&closing) else {
+                return lowercase.len();
+            };
+            let close = content_start + close;
+            return lowercase[close..]
+                .find('>')
+                .map_or(lowercase.len(), |end| close + end + 1);
+        }
+        pos + 1
+    }
+
+    /// Parses a heading line, eg "== History ==", including trailing whitespace and comments.
+    fn parse_heading(wikitext: &str, start: usize, end: usize) -> Option<WikitextSection> {
+        let line = &wikitext[start..end];
+        let mut trimmed = line.trim_end();
+        while trimmed.ends_with("-->") {
+            let comment_start = trimmed.rfind("<!--")?;
+            trimmed = trimmed[..comment_start].trim_end();
+        }
+        let leading = trimmed.len() - trimmed.trim_start_matches('=').len();
+        let trailing = trimmed.len() - trimmed.trim_end_matches('=').len();
+        let level = leading.min(trailing).min(6);
+        if level == 0 || trimmed.len() <= level * 2 {
+            return None;
+        }
+        let title = Self::remove_comments(&trimmed[level..trimmed.len() - level]);
+        Some(WikitextSection {
+            heading: line.trim_end().to_string(),
+            anchor: Self::anchor(&title),
+            title,
+            level,
+            offset: start,
+            end,
+        })
+    }
+
+    /// Removes comments from a heading title, and trims it.
+    fn remove_comments(title: &str) -> String {
+        let mut text = String::new();
+        let mut rest = title;
+        while let Some(start) = rest.find("<!--") {
+            text += &rest[..start];
+            rest = rest[start..]
+                .find("-->")
+                .map_or("", |end| &rest[start + end + 3..]);
+        }
+        text += rest;
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Sets the end of each section to the start of the next section of the same or a higher level.
+    fn set_section_ends(sections: &mut [WikitextSection], text_end: usize) {
+        for num in 0..sections.len() {
+            let level = sections[num].level;
+            sections[num].end = sections[num + 1..]
+                .iter()
+                .find(|section| section.level <= level)
+                .map_or(text_end, |section| section.offset);
+        }
+    }
+
+    /// Generates an anchor from a heading title, removing basic markup.
+    fn anchor(title: &str) -> String {
+        let mut text = String::new();
+        let mut rest = title;
+        while let Some(pos) = rest.find("[[") {
+            text += &rest[..pos];
+            let Some(end) = rest[pos..].find("]]") else {
+                break;
+            };
+            let link = &rest[pos + 2..pos + end];
+            text += link.rsplit('|').next().unwrap_or(link);
+            rest = &rest[pos + end + 2..];
+        }
+        text += rest;
+        let mut anchor = String::new();
+        let mut in_tag = false;
+        for c in text.replace("'''", "").replace("''", "").chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' if in_tag => in_tag = false,
+                _ if in_tag => {}
+                ' ' => anchor.push('_'),
+                _ => anchor.push(c),
+            }
+        }
+        anchor.trim_matches('_').to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_WIKITEXT: &str = "Lead text.\n\
+        == History ==\n\
+        Some history.\n\
+        === Early [[year]]s ===\n\
+        Early.\n\
+        == Design <!-- comment --> ==\n\
+        <nowiki>\n== Not a heading ==\n</nowiki>\n\
+        {{Quote|\n== Not a heading either ==\n}}\n\
+        <!--\n== Commented out ==\n-->\n\
+        == See also ==\n\
+        * [[Foo]]\n";
+
+    #[test]
+    fn test_parse() {
+        let sections = WikitextSections::parse(TEST_WIKITEXT);
+        let titles: Vec<_> = sections.sections().iter().map(|s| &s.title).collect();
+        assert_eq!(titles, ["History", "Early [[year]]s", "Design", "See also"]);
+        assert_eq!(sections.sections()[0].heading, "== History ==");
+        assert_eq!(sections.sections()[0].offset, 11);
+        assert_eq!(sections.sections()[1].level, 3);
+        assert_eq!(sections.sections()[1].anchor, "Early_years");
+        assert_eq!(
+            sections.sections()[2].heading,
+            "== Design <!-- comment --> =="
+        );
+    }
+
+    #[test]
+    fn test_section_text() {
+        let sections = WikitextSections::parse(TEST_WIKITEXT);
+        assert_eq!(sections.section_text(0), Some("Lead text.\n"));
+        assert_eq!(
+            sections.section_text(1),
+            Some("== History ==\nSome history.\n=== Early [[year]]s ===\nEarly.\n")
+        );
+        assert_eq!(
+            sections.section_text(2),
+            Some("=== Early [[year]]s ===\nEarly.\n")
+        );
+        assert_eq!(
+            sections.section_text(4),
+            Some("== See also ==\n* [[Foo]]\n")
+        );
+        assert_eq!(sections.section_text(5), None);
+        assert_eq!(sections.find("See also"), Some(4));
+    }
+
+    #[test]
+    fn test_replace_section() {
+        let mut sections = WikitextSections::parse(TEST_WIKITEXT);
+        sections
+            .replace_section(1, "== Background ==\nNew text.")
+            .expect("Section should exist");
+        assert!(
+            sections
+                .wikitext()
+                .starts_with("Lead text.\n== Background ==\nNew text.\n== Design")
+        );
+        assert_eq!(sections.sections().len(), 3);
+        assert!(sections.replace_section(9, "").is_err());
+    }
+
+    #[test]
+    fn test_remove_section() {
+        let mut sections = WikitextSections::parse(TEST_WIKITEXT);
+        sections.remove_section(4).expect("Section should exist");
+        assert!(
+            sections
+                .wikitext()
+                .ends_with("<!--\n== Commented out ==\n-->\n")
+        );
+        assert!(sections.remove_section(0).is_err());
+    }
+
+    #[test]
+    fn test_append_section() {
+        let mut sections = WikitextSections::parse(TEST_WIKITEXT);
+        sections.append_section("Report", 2, "All good.\n");
+        assert!(
+            sections
+                .wikitext()
+                .ends_with("* [[Foo]]\n\n== Report ==\nAll good.\n")
+        );
+        assert_eq!(sections.find("Report"), Some(5));
+    }
+
+    #[test]
+    fn test_insert_section() {
+        let mut sections = WikitextSections::parse(TEST_WIKITEXT);
+        sections
+            .insert_section(4, "Usage", 2, "Used.")
+            .expect("Section should exist");
+        assert_eq!(sections.section_text(4), Some("== Usage ==\nUsed.\n\n"));
+        assert_eq!(sections.find("See also"), Some(5));
+        assert!(sections.insert_section(0, "Lead", 2, "").is_err());
+        sections
+            .insert_section(6, "Last", 2, "")
+            .expect("Appending should work");
+        assert_eq!(sections.find("Last"), Some(6));
+    }
+
+    #[test]
+    fn test_anchor() {
+        assert_eq!(
+            WikitextSections::anchor("The '''[[Foo|bar]]''' <span>baz</span>"),
+            "The_bar_baz"
+        );
+    }
+}
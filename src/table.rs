@@ -0,0 +1,374 @@
+use crate::{
+    html::{ROW_SELECTOR, visible_text},
+    utilities::csv_field,
+};
+use scraper::ElementRef;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// HTML limits for `colspan` and `rowspan`, see the HTML standard.
+const MAX_COLSPAN: usize = 1000;
+const MAX_ROWSPAN: usize = 65534;
+
+/// An infobox, as key/value pairs in document order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Infobox {
+    /// The name of the template that generated the infobox, eg `Infobox programming language`
+    pub template: Option<String>,
+    /// The title row of the infobox, if any
+    pub title: Option<String>,
+    /// The rows with a label and a value, in document order
+    pub fields: Vec<(String, String)>,
+}
+
+impl Infobox {
+    /// Parses an infobox table. Rows with exactly one header cell and one data cell become fields.
+    pub(crate) fn from_element(table: &ElementRef, template: Option<String>) -> Self {
+        let mut ret = Self {
+            template,
+            ..Default::default()
+        };
+        for row in table_rows(table) {
+            let cells: Vec<ElementRef> = row
+                .child_elements()
+                .filter(|cell| matches!(cell.value().name(), "th" | "td"))
+                .collect();
+            match cells.as_slice() {
+                [label, value] if label.value().name() == "th" && value.value().name() == "td" => {
+                    let label = visible_text(label);
+                    if !label.is_empty() {
+                        ret.fields.push((label, visible_text(value)));
+                    }
+                }
+                [above] if above.value().name() == "th" && ret.title.is_none() => {
+                    ret.title = Some(visible_text(above)).filter(|title| !title.is_empty());
+                }
+                _ => {}
+            }
+        }
+        if ret.title.is_none() {
+            ret.title = table
+                .child_elements()
+                .find(|child| child.value().name() == "caption")
+                .map(|caption| visible_text(&caption));
+        }
+        ret
+    }
+
+    /// Returns the value of the first field with the label `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(label, _)| label == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the fields as CSV, with a `key,value` header row.
+    pub fn to_csv(&self) -> String {
+        let mut ret = "key,value\n".to_string();
+        for (key, value) in &self.fields {
+            ret += &format!("{},{}\n", csv_field(key), csv_field(value));
+        }
+        ret
+    }
+
+    /// Returns the fields as a JSON object. Repeated labels get a numeric suffix, eg `Website (2)`.
+    pub fn to_json(&self) -> Value {
+        Value::Object(unique_keys(&self.fields))
+    }
+}
+
+/// A table as a row-major grid, with `rowspan` and `colspan` cells repeated in every grid cell they cover.
+/// All rows have the same length.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Table {
+    pub caption: Option<String>,
+    /// The number of leading rows that consist of header cells only
+    pub header_rows: usize,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Parses a table into a grid. Nested tables are part of the text of their cell.
+    pub(crate) fn from_element(table: &ElementRef) -> Self {
+        // For every column: the text of a cell spanning into following rows, and the number of rows left
+        let mut spans: Vec<Option<(String, usize)>> = vec![];
+        let mut rows = vec![];
+        let mut header_rows = 0;
+        let mut in_header = true;
+        let rows_in_sections = table_sections(table).into_iter().flat_map(|section| {
+            let len = section.len();
+            section
+                .into_iter()
+                .enumerate()
+                .map(move |(index, row)| (row, len - index))
+        });
+        for (row, rows_left) in rows_in_sections {
+            let mut grid_row: Vec<String> = vec![];
+            let mut only_headers = true;
+            let cells = row
+                .child_elements()
+                .filter(|cell| matches!(cell.value().name(), "th" | "td"));
+            for cell in cells {
+                Self::fill_spans(&mut spans, &mut grid_row, false);
+                only_headers &= cell.value().name() == "th";
+                let span = |name: &str| {
+                    cell.attr(name)
+                        .and_then(|span| span.trim().parse::<usize>().ok())
+                };
+                let colspan = span("colspan").map_or(1, |span| span.clamp(1, MAX_COLSPAN));
+                // `rowspan="0"` spans the remaining rows of the row group
+                let rowspan = match span("rowspan") {
+                    Some(0) => rows_left,
+                    span => span.map_or(1, |span| span.clamp(1, MAX_ROWSPAN)),
+                };
+                let text = visible_text(&cell);
+                for _ in 0..colspan {
+                    let column = grid_row.len();
+                    if spans.len() <= column {
+                        spans.resize(column + 1, None);
+                    }
+                    if rowspan > 1 {
+                        spans[column] = Some((text.clone(), rowspan - 1));
+                    }
+                    grid_row.push(text.clone());
+                }
+            }
+            Self::fill_spans(&mut spans, &mut grid_row, true);
+            if grid_row.is_empty() {
+                continue;
+            }
+            if in_header && only_headers {
+                header_rows += 1;
+            } else {
+                in_header = false;
+            }
+            rows.push(grid_row);
+        }
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        rows.iter_mut()
+            .for_each(|row| row.resize(width, String::new()));
+        Self {
+            caption: table
+                .child_elements()
+                .find(|child| child.value().name() == "caption")
+                .map(|caption| visible_text(&caption)),
+            header_rows,
+            rows,
+        }
+    }
+
+    /// Returns the column names, from the last header row.
+    pub fn headers(&self) -> Option<&[String]> {
+        self.header_rows
+            .checked_sub(1)
+            .and_then(|last| self.rows.get(last))
+            .map(Vec::as_slice)
+    }
+
+    /// Returns the rows after the header rows.
+    pub fn body(&self) -> &[Vec<String>] {
+        &self.rows[self.header_rows.min(self.rows.len())..]
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
+    }
+
+    /// Returns the whole grid as CSV, including header rows.
+    pub fn to_csv(&self) -> String {
+        let mut ret = String::new();
+        for row in &self.rows {
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            ret += &row.join(",");
+            ret.push('\n');
+        }
+        ret
+    }
+
+    /// Returns the body rows as JSON objects keyed by the column names, or as arrays if the table has no header.
+    /// Repeated column names get a numeric suffix, eg `Year (2)`.
+    pub fn to_json(&self) -> Value {
+        let headers = self.headers();
+        let rows = self.body().iter().map(|row| {
+            headers.map_or_else(
+                || Value::from(row.clone()),
+                |headers| {
+                    let fields: Vec<(String, String)> =
+                        headers.iter().cloned().zip(row.iter().cloned()).collect();
+                    Value::Object(unique_keys(&fields))
+                },
+            )
+        });
+        Value::Array(rows.collect())
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Fills the columns at the current end of `row` that are covered by cells from previous rows.
+    /// If `to_end` is set, all remaining spanning columns are filled.
+    fn fill_spans(spans: &mut [Option<(String, usize)>], row: &mut Vec<String>, to_end: bool) {
+        while let Some(slot) = spans.get_mut(row.len()) {
+            match slot.take() {
+                Some((text, rows_left)) => {
+                    if rows_left > 1 {
+                        *slot = Some((text.clone(), rows_left - 1));
+                    }
+                    row.push(text);
+                }
+                None if to_end && spans[row.len()..].iter().any(Option::is_some) => {
+                    row.push(String::new());
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// Returns the rows of a table, without rows of nested tables.
+fn table_rows<'a>(table: &ElementRef<'a>) -> impl Iterator<Item = ElementRef<'a>> {
+    table_sections(table).into_iter().flatten()
+}
+
+/// Returns the rows of a table by row group: `thead`, `tbody` and `tfoot`,
+/// and consecutive rows directly in the table, which form an implicit `tbody`.
+fn table_sections<'a>(table: &ElementRef<'a>) -> Vec<Vec<ElementRef<'a>>> {
+    let mut ret: Vec<Vec<ElementRef>> = vec![];
+    let mut implicit = false;
+    for child in table.child_elements() {
+        match child.value().name() {
+            "thead" | "tbody" | "tfoot" => {
+                let rows = child.child_elements();
+                ret.push(rows.filter(|row| ROW_SELECTOR.matches(row)).collect());
+                implicit = false;
+            }
+            _ if ROW_SELECTOR.matches(&child) => match ret.last_mut() {
+                Some(section) if implicit => section.push(child),
+                _ => {
+                    ret.push(vec![child]);
+                    implicit = true;
+                }
+            },
+            _ => {}
+        }
+    }
+    ret
+}
+
+/// Converts key/value pairs into a JSON object, adding a numeric suffix to repeated keys.
+fn unique_keys(fields: &[(String, String)]) -> Map<String, Value> {
+    let mut ret = Map::new();
+    for (key, value) in fields {
+        let mut unique = key.clone();
+        let mut number = 1;
+        while ret.contains_key(&unique) {
+            number += 1;
+            unique = format!("{key} ({number})");
+        }
+        ret.insert(unique, Value::from(value.as_str()));
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html::ParsoidDocument;
+    use serde_json::json;
+
+    const TABLE: &str = r##"<table class="wikitable"><caption>Releases</caption>
+        <thead><tr><th>Version</th><th>Date</th><th>Notes</th></tr></thead>
+        <tbody>
+        <tr><td rowspan="2">1.0</td><td>2015</td><td>First<sup class="reference"><a href="#cite_note-1">[1]</a></sup></td></tr>
+        <tr><td colspan="2">Same year, "stable"</td></tr>
+        <tr><td>2.0</td><td>2021</td></tr>
+        <tr><td>3.0</td><td rowspan="0">Later<br/>maybe</td><td><table><tr><td>nested</td></tr></table></td></tr>
+        <tr><td>4.0</td><td>Soon</td></tr>
+        </tbody>
+        <tfoot><tr><td colspan="3">Footer</td></tr></tfoot></table>"##;
+
+    fn test_table() -> Table {
+        let document = ParsoidDocument::parse(TABLE);
+        document.tables().pop().expect("Table missing")
+    }
+
+    #[test]
+    fn test_table_grid() {
+        let table = test_table();
+        assert_eq!(table.caption.as_deref(), Some("Releases"));
+        assert_eq!(table.header_rows, 1);
+        assert_eq!(table.width(), 3);
+        assert_eq!(
+            table.headers().expect("Headers missing"),
+            ["Version", "Date", "Notes"]
+        );
+        assert_eq!(
+            table.body(),
+            [
+                vec!["1.0", "2015", "First"],
+                vec!["1.0", "Same year, \"stable\"", "Same year, \"stable\""],
+                vec!["2.0", "2021", ""],
+                vec!["3.0", "Later\nmaybe", "nested"],
+                // `rowspan="0"` ends with the `tbody`
+                vec!["4.0", "Later\nmaybe", "Soon"],
+                vec!["Footer", "Footer", "Footer"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_export() {
+        let table = test_table();
+        let csv = table.to_csv();
+        assert!(csv.starts_with("Version,Date,Notes\n1.0,2015,First\n"));
+        assert!(csv.contains("1.0,\"Same year, \"\"stable\"\"\",\"Same year, \"\"stable\"\"\"\n"));
+        assert!(csv.ends_with(
+            "3.0,\"Later\nmaybe\",nested\n4.0,\"Later\nmaybe\",Soon\nFooter,Footer,Footer\n"
+        ));
+        let json = table.to_json();
+        assert_eq!(json.as_array().map(Vec::len), Some(6));
+        assert_eq!(
+            json[2],
+            json!({"Version": "2.0", "Date": "2021", "Notes": ""})
+        );
+    }
+
+    #[test]
+    fn test_infoboxes() {
+        let html =
+            std::fs::read_to_string("test_data/page_get_html.html").expect("Test file missing");
+        let document = ParsoidDocument::parse(&html);
+        let infoboxes = document.infoboxes();
+        assert_eq!(infoboxes.len(), 2);
+        let infobox = &infoboxes[0];
+        assert_eq!(
+            infobox.template.as_deref(),
+            Some("Infobox programming language")
+        );
+        assert_eq!(infobox.title.as_deref(), Some("Rust"));
+        assert_eq!(
+            infobox.get("Paradigms"),
+            Some("Concurrent\nfunctional\ngeneric\nimperative\nstructured")
+        );
+        assert!(infobox.fields.iter().all(|(key, _)| !key.is_empty()));
+        assert!(infobox.to_csv().starts_with("key,value\nParadigms,"));
+        assert_eq!(
+            infobox.to_json()["Paradigms"],
+            "Concurrent\nfunctional\ngeneric\nimperative\nstructured"
+        );
+    }
+
+    #[test]
+    fn test_unique_keys() {
+        let fields = vec![
+            ("a".to_string(), "1".to_string()),
+            ("a".to_string(), "2".to_string()),
+        ];
+        assert_eq!(
+            Value::Object(unique_keys(&fields)),
+            json!({"a": "1", "a (2)": "2"})
+        );
+    }
+}
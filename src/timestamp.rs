@@ -0,0 +1,306 @@
+use crate::error::RestApiError;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, SubsecRound, Utc};
+
+/// The timestamp format used by the `MediaWiki` REST API.
+#[cfg(feature = "chrono")]
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// The normalized timestamp value: a UTC datetime with the `chrono` feature, seconds since the Unix epoch otherwise.
+#[cfg(feature = "chrono")]
+type Inner = DateTime<Utc>;
+#[cfg(not(feature = "chrono"))]
+type Inner = i64;
+
+/// An ISO 8601 timestamp, as returned by the `MediaWiki` REST API (eg `2025-10-15T07:59:08Z`).
+/// Timestamps are validated when they are parsed or deserialized, and stored as UTC with second precision,
+/// so timestamps with different offsets compare chronologically.
+/// They are serialized in the API format.
+/// With the `chrono` feature, timestamps can be converted from and to `chrono::DateTime<Utc>`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Timestamp(Inner);
+
+impl Timestamp {
+    /// Returns the UTC datetime.
+    #[cfg(feature = "chrono")]
+    pub const fn to_datetime(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = RestApiError;
+
+    #[cfg(feature = "chrono")]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let datetime = DateTime::parse_from_rfc3339(s)
+            .map_err(|_| RestApiError::InvalidTimestamp(s.to_string()))?;
+        Ok(Self(datetime.with_timezone(&Utc).trunc_subsecs(0)))
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_rfc3339(s)
+            .map(Self)
+            .ok_or_else(|| RestApiError::InvalidTimestamp(s.to_string()))
+    }
+}
+
+impl TryFrom<&str> for Timestamp {
+    type Error = RestApiError;
+
+    fn try_from(timestamp: &str) -> Result<Self, Self::Error> {
+        timestamp.parse()
+    }
+}
+
+impl TryFrom<String> for Timestamp {
+    type Error = RestApiError;
+
+    fn try_from(timestamp: String) -> Result<Self, Self::Error> {
+        timestamp.parse()
+    }
+}
+
+impl From<Timestamp> for String {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.to_string()
+    }
+}
+
+impl fmt::Display for Timestamp {
+    #[cfg(feature = "chrono")]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.format(TIMESTAMP_FORMAT))
+    }
+
+    #[cfg(not(feature = "chrono"))]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = self.0.div_euclid(86400);
+        let seconds = self.0.rem_euclid(86400);
+        let (year, month, day) = civil_from_days(days);
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    }
+}
+
+/// Compares to a timestamp string; an invalid string is never equal.
+impl PartialEq<&str> for Timestamp {
+    fn eq(&self, other: &&str) -> bool {
+        other.parse::<Self>().is_ok_and(|other| *self == other)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(datetime: DateTime<Utc>) -> Self {
+        Self(datetime.trunc_subsecs(0))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Timestamp {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Timestamp".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "format": "date-time",
+        })
+    }
+}
+
+/// Parses an RFC 3339 timestamp (eg `2025-10-15T07:59:08Z` or `2025-10-15T09:59:08.5+02:00`)
+/// into seconds since the Unix epoch. Fractional seconds are dropped.
+#[cfg(not(feature = "chrono"))]
+fn parse_rfc3339(s: &str) -> Option<i64> {
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = s.get(range)?;
+        if digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    };
+    let bytes = s.as_bytes();
+    if bytes.len() < 20
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !matches!(bytes[10], b'T' | b't' | b' ')
+        || bytes[13] != b':'
+        || bytes[16] != b':'
+    {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+    let mut rest = &s[19..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        rest = &fraction[digits..];
+    }
+    let offset = match rest.as_bytes() {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+            let (offset_hour, offset_minute) = (
+                number(s.len() - 5..s.len() - 3)?,
+                number(s.len() - 2..s.len())?,
+            );
+            if offset_hour > 23 || offset_minute > 59 {
+                return None;
+            }
+            let offset = offset_hour * 3600 + offset_minute * 60;
+            if *sign == b'-' { -offset } else { offset }
+        }
+        _ => return None,
+    };
+    Some(days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+#[cfg(not(feature = "chrono"))]
+const fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days since the Unix epoch for a date in the proleptic Gregorian calendar.
+#[cfg(not(feature = "chrono"))]
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the date (year, month, day) for a number of days since the Unix epoch.
+#[cfg(not(feature = "chrono"))]
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialization() {
+        let timestamp: Timestamp =
+            serde_json::from_str("\"2025-10-15T07:59:08Z\"").expect("Failed to deserialize");
+        assert_eq!(timestamp, "2025-10-15T07:59:08Z");
+        assert_eq!(
+            serde_json::to_string(&timestamp).expect("Failed to serialize"),
+            "\"2025-10-15T07:59:08Z\""
+        );
+        assert!(serde_json::from_str::<Timestamp>("\"yesterday\"").is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let timestamp: Timestamp = "2024-02-29T23:30:00.25-01:30"
+            .parse()
+            .expect("Failed to parse timestamp");
+        assert_eq!(timestamp.to_string(), "2024-03-01T01:00:00Z");
+        assert_eq!(Timestamp::default().to_string(), "1970-01-01T00:00:00Z");
+        for invalid in [
+            "yesterday",
+            "2025-10-15",
+            "2025-10-15T07:59:08",
+            "2025-02-29T00:00:00Z",
+            "2025-13-01T00:00:00Z",
+            "2025-10-15T24:00:00Z",
+            "2025-10-15T07:59:08+0200",
+        ] {
+            assert!(
+                matches!(
+                    Timestamp::try_from(invalid),
+                    Err(RestApiError::InvalidTimestamp(_))
+                ),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut timestamps: Vec<Timestamp> = [
+            "2025-10-22T14:47:24Z",
+            "2024-01-01T00:00:00Z",
+            "2025-10-15T07:59:08Z",
+            // Later than 2025-10-22T14:47:24Z, although it sorts first as a string
+            "2025-10-22T10:00:00-05:00",
+        ]
+        .into_iter()
+        .map(|timestamp| timestamp.parse().expect("Failed to parse timestamp"))
+        .collect();
+        timestamps.sort();
+        assert_eq!(timestamps[0], "2024-01-01T00:00:00Z");
+        assert_eq!(timestamps[2], "2025-10-22T14:47:24Z");
+        assert_eq!(timestamps[3], "2025-10-22T15:00:00Z");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_datetime() {
+        use chrono::{Datelike, Timelike};
+        let timestamp: Timestamp = "2025-10-15T07:59:08Z"
+            .parse()
+            .expect("Failed to parse timestamp");
+        let datetime = timestamp.to_datetime();
+        assert_eq!(datetime.year(), 2025);
+        assert_eq!(datetime.hour(), 7);
+        assert_eq!(Timestamp::from(datetime), timestamp);
+        assert_eq!(DateTime::<Utc>::from(timestamp), datetime);
+    }
+}
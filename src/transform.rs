@@ -1,11 +1,238 @@
 use crate::{
+    bulk::{BulkStream, run_concurrent},
     error::RestApiError,
-    prelude::{Lint, RestApi},
+    prelude::{Lint, RestApi, RevisionId},
+    utilities::content_hash,
 };
-use serde_json::json;
-use std::collections::HashMap;
+use serde_json::{Value, json};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
+    sync::Arc,
+};
+use tokio::sync::RwLock;
 use urlencoding::encode;
 
+/// The content type of the Parsoid HTML, as served by the REST API
+const PARSOID_HTML_CONTENT_TYPE: &str =
+    "text/html; charset=utf-8; profile=\"https://www.mediawiki.org/wiki/Specs/HTML/2.8.0\"";
+
+/// The default number of results kept by a `TransformCache`.
+pub const DEFAULT_TRANSFORM_CACHE_CAPACITY: usize = 1000;
+
+/// Transform results, memoized by the API URL, the direction, and the SHA-1 hash of the input.
+/// Clones share the same entries, so one cache can be used by many batches and tasks.
+/// Once the capacity is reached, the oldest results are evicted first.
+#[derive(Clone, Debug)]
+pub struct TransformCache {
+    entries: Arc<RwLock<CacheEntries>>,
+    capacity: usize,
+}
+
+/// Cached results, and their keys in insertion order.
+#[derive(Debug, Default)]
+struct CacheEntries {
+    results: HashMap<String, String>,
+    order: VecDeque<String>,
+}
+
+impl Default for TransformCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_TRANSFORM_CACHE_CAPACITY)
+    }
+}
+
+impl TransformCache {
+    /// Creates a cache for up to `DEFAULT_TRANSFORM_CACHE_CAPACITY` results.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache for up to `capacity` results.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(RwLock::new(CacheEntries::default())),
+            capacity,
+        }
+    }
+
+    /// Returns the maximum number of cached results.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of cached results.
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.results.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.entries.read().await.results.is_empty()
+    }
+
+    /// Removes all cached results.
+    pub async fn clear(&self) {
+        let mut entries = self.entries.write().await;
+        entries.results.clear();
+        entries.order.clear();
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    fn key(api: &RestApi, direction: &str, content: &str) -> String {
+        format!("{}|{direction}|{}", api.api_url(), content_hash(content))
+    }
+
+    async fn get(&self, key: &str) -> Option<String> {
+        self.entries.read().await.results.get(key).cloned()
+    }
+
+    async fn insert(&self, key: String, value: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.write().await;
+        if entries.results.insert(key.clone(), value).is_none() {
+            entries.order.push_back(key);
+        }
+        while entries.results.len() > self.capacity {
+            match entries.order.pop_front() {
+                Some(oldest) => entries.results.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+}
+
+/// Options for `Transform::wikitext2html_with` and `Transform::html2wikitext_with`.
+/// Options that are not set are not sent, so the server defaults apply.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransformOptions {
+    title: Option<String>,
+    revision: Option<RevisionId>,
+    body_only: Option<bool>,
+    stash: Option<bool>,
+    original_html: Option<String>,
+    original_content_type: Option<String>,
+    etag: Option<String>,
+    accept: Option<String>,
+}
+
+impl TransformOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the title of the page, as context for the transform.
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets the revision the content belongs to. Requires a title.
+    pub const fn with_revision(mut self, revision: RevisionId) -> Self {
+        self.revision = Some(revision);
+        self
+    }
+
+    /// Returns only the content of the `<body>`, without the `<html>` and `<head>` wrapper (wikitext to HTML only).
+    pub const fn with_body_only(mut self, body_only: bool) -> Self {
+        self.body_only = Some(body_only);
+        self
+    }
+
+    /// Stashes the rendering on the server, for a later HTML to wikitext transform (wikitext to HTML only).
+    pub const fn with_stash(mut self, stash: bool) -> Self {
+        self.stash = Some(stash);
+        self
+    }
+
+    /// Sets the unmodified Parsoid HTML of the revision, and the `ETag` it was served with, if known,
+    /// for selective serialization (HTML to wikitext only).
+    pub fn with_original_html<S: Into<String>>(mut self, html: S, etag: Option<&str>) -> Self {
+        self.original_html = Some(html.into());
+        self.etag = etag.map(ToString::to_string);
+        self
+    }
+
+    /// Sets the content type of the original HTML, as it was served.
+    /// By default, the Parsoid HTML content type of the REST API is assumed.
+    pub fn with_original_content_type<S: Into<String>>(mut self, content_type: S) -> Self {
+        self.original_content_type = Some(content_type.into());
+        self
+    }
+
+    /// Sets the `Accept` header. By default, `text/html` is requested for HTML and `text/plain` for wikitext.
+    pub fn with_accept<S: Into<String>>(mut self, content_type: S) -> Self {
+        self.accept = Some(content_type.into());
+        self
+    }
+
+    /// Requests a specific version of the Parsoid HTML spec, eg `2.8.0`.
+    pub fn with_html_profile(self, version: &str) -> Self {
+        self.with_accept(format!(
+            "text/html; charset=utf-8; profile=\"https://www.mediawiki.org/wiki/Specs/HTML/{version}\""
+        ))
+    }
+
+    /// Requests a specific version of the wikitext spec, eg `1.0.0`.
+    pub fn with_wikitext_profile(self, version: &str) -> Self {
+        self.with_accept(format!(
+            "text/plain; charset=utf-8; profile=\"https://www.mediawiki.org/wiki/Specs/wikitext/{version}\""
+        ))
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Returns the endpoint path, eg `/transform/html/to/wikitext/Foo/123`.
+    fn path(&self, from: &str, to: &str) -> Result<String, RestApiError> {
+        let mut path = format!("/transform/{from}/to/{to}");
+        match (&self.title, self.revision) {
+            (Some(title), revision) => {
+                path += &format!("/{}", encode(title));
+                if let Some(revision) = revision {
+                    path += &format!("/{revision}");
+                }
+            }
+            (None, Some(revision)) => return Err(RestApiError::TransformTitleRequired(revision)),
+            (None, None) => {}
+        }
+        Ok(path)
+    }
+
+    /// Returns the request body, with `content` under `key`.
+    fn body(&self, key: &str, content: String) -> Value {
+        let mut body = json!({ key: content });
+        if let Some(body_only) = self.body_only {
+            body["body_only"] = json!(body_only);
+        }
+        if let Some(stash) = self.stash {
+            body["stash"] = json!(stash);
+        }
+        if let Some(original_html) = &self.original_html {
+            let content_type = self
+                .original_content_type
+                .as_deref()
+                .unwrap_or(PARSOID_HTML_CONTENT_TYPE);
+            let mut original = json!({
+                "html": {
+                    "headers": {
+                        "content-type": content_type
+                    },
+                    "body": original_html
+                }
+            });
+            if let Some(revision) = self.revision {
+                original["revid"] = json!(revision);
+            }
+            body["original"] = original;
+        }
+        body
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Transform;
 
@@ -125,6 +352,112 @@ impl Transform {
         Ok(ret)
     }
 
+    /// Transforms HTML to wikitext using selective serialization ("selser").
+    /// `original_html` is the unmodified Parsoid HTML of `revision`, as retrieved for editing,
+    /// and `etag` the `ETag` it was served with, if known.
+    /// Only the parts of `html` that differ from the original are re-serialized,
+    /// so the rest of the wikitext keeps its formatting.
+    pub async fn html2wikitext_selser<S1: Into<String>, S2: Into<String>>(
+        html: S1,
+        title: S2,
+        revision: RevisionId,
+        original_html: &str,
+        etag: Option<&str>,
+        api: &RestApi,
+    ) -> Result<String, RestApiError> {
+        let options = TransformOptions::new()
+            .with_title(title)
+            .with_revision(revision)
+            .with_original_html(original_html, etag);
+        Self::html2wikitext_with(html, &options, api).await
+    }
+
+    /// Transforms wikitext to HTML, with `options` for the title, revision, `body_only`, stash, and `Accept` profile.
+    /// Returns the HTML and its `ETag`, if any, as `Revision::get_html_with_etag` does,
+    /// so a stashed rendering can be transformed back with `TransformOptions::with_original_html`.
+    pub async fn wikitext2html_with<S: Into<String>>(
+        wikitext: S,
+        options: &TransformOptions,
+        api: &RestApi,
+    ) -> Result<(String, Option<String>), RestApiError> {
+        let path = options.path("wikitext", "html")?;
+        let body = options.body("wikitext", wikitext.into());
+        Self::transform_with(path, &body, options, "text/html", api).await
+    }
+
+    /// Transforms HTML to wikitext, with `options` for the title, revision, original HTML, and `Accept` profile.
+    pub async fn html2wikitext_with<S: Into<String>>(
+        html: S,
+        options: &TransformOptions,
+        api: &RestApi,
+    ) -> Result<String, RestApiError> {
+        let path = options.path("html", "wikitext")?;
+        let body = options.body("html", html.into());
+        let (wikitext, _etag) =
+            Self::transform_with(path, &body, options, "text/plain", api).await?;
+        Ok(wikitext)
+    }
+
+    /// Transforms many wikitexts to HTML, with at most `concurrency` requests running in parallel.
+    /// Identical wikitexts are transformed only once, and results found in `cache` are not requested again.
+    /// Yields `(wikitext, result)` pairs for every distinct wikitext, in completion order,
+    /// or in input order if `preserve_order` is set.
+    /// Errors are reported per wikitext, are not cached, and do not abort the batch.
+    pub fn wikitext2html_many<'a, I, S>(
+        api: &'a RestApi,
+        wikitexts: I,
+        cache: &'a TransformCache,
+        concurrency: usize,
+        preserve_order: bool,
+    ) -> BulkStream<'a, String, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::transform_many(
+            api,
+            wikitexts,
+            cache,
+            "wikitext2html",
+            concurrency,
+            preserve_order,
+            move |wikitext| async move {
+                let (html, _etag) =
+                    Self::wikitext2html_with(wikitext, &TransformOptions::new(), api).await?;
+                Ok(html)
+            },
+        )
+    }
+
+    /// Transforms many HTML documents to wikitext, with at most `concurrency` requests running in parallel.
+    /// Identical HTML is transformed only once, and results found in `cache` are not requested again.
+    /// Yields `(html, result)` pairs for every distinct HTML input, in completion order,
+    /// or in input order if `preserve_order` is set.
+    /// Errors are reported per input, are not cached, and do not abort the batch.
+    pub fn html2wikitext_many<'a, I, S>(
+        api: &'a RestApi,
+        htmls: I,
+        cache: &'a TransformCache,
+        concurrency: usize,
+        preserve_order: bool,
+    ) -> BulkStream<'a, String, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::transform_many(
+            api,
+            htmls,
+            cache,
+            "html2wikitext",
+            concurrency,
+            preserve_order,
+            move |html| async move {
+                Self::html2wikitext_with(html, &TransformOptions::new(), api).await
+            },
+        )
+    }
+
     /// Returns lint errors for wikitext.
     pub async fn wikitext2lint<S: Into<String>>(
         wikitext: S,
@@ -175,13 +508,98 @@ impl Transform {
         let ret: Vec<Lint> = response.json().await?;
         Ok(ret)
     }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Posts a transform request built from `options`. Returns the response body and its `ETag`, if any.
+    async fn transform_with(
+        path: String,
+        body: &Value,
+        options: &TransformOptions,
+        default_accept: &str,
+        api: &RestApi,
+    ) -> Result<(String, Option<String>), RestApiError> {
+        let params = HashMap::new();
+        let mut request = api
+            .build_request(path, params, reqwest::Method::POST)
+            .await?
+            .body(body.to_string())
+            .build()?;
+        request
+            .headers_mut()
+            .insert(reqwest::header::CONTENT_TYPE, "application/json".parse()?);
+        let accept = options.accept.as_deref().unwrap_or(default_accept);
+        request
+            .headers_mut()
+            .insert(reqwest::header::ACCEPT, accept.parse()?);
+        if let Some(etag) = &options.etag {
+            request
+                .headers_mut()
+                .insert(reqwest::header::IF_MATCH, etag.parse()?);
+        }
+        let response = api.execute(request).await?;
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| etag.to_string());
+        let text = Self::text_or_error(response).await?;
+        Ok((text, etag))
+    }
+
+    /// Returns the response body, or an error for a response with an error status,
+    /// so that error pages are not mistaken for transform results.
+    async fn text_or_error(response: reqwest::Response) -> Result<String, RestApiError> {
+        if !response.status().is_success() {
+            return Err(RestApiError::from_response(response).await);
+        }
+        Ok(response.text().await?)
+    }
+
+    /// Deduplicates `inputs`, and runs `transform` for those not found in `cache`.
+    fn transform_many<'a, I, S, F, Fut>(
+        api: &'a RestApi,
+        inputs: I,
+        cache: &'a TransformCache,
+        direction: &'static str,
+        concurrency: usize,
+        preserve_order: bool,
+        transform: F,
+    ) -> BulkStream<'a, String, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+        F: Fn(String) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<String, RestApiError>> + Send + 'a,
+    {
+        let mut seen = HashSet::new();
+        let inputs = inputs
+            .into_iter()
+            .map(Into::into)
+            .filter(|input: &String| seen.insert(input.clone()))
+            .collect();
+        run_concurrent(inputs, concurrency, preserve_order, move |input| {
+            let key = TransformCache::key(api, direction, &input);
+            let future = transform(input);
+            async move {
+                if let Some(ret) = cache.get(&key).await {
+                    return Ok(ret);
+                }
+                let ret = future.await?;
+                cache.insert(key, ret.clone()).await;
+                Ok(ret)
+            }
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::StreamExt;
     use serde_json::Value;
-    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::matchers::{body_json, body_partial_json, header, method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
@@ -278,6 +696,103 @@ mod tests {
         assert_eq!(wikitext, expected_wikitext);
     }
 
+    #[tokio::test]
+    async fn test_wikitext2html_many() {
+        let mock_path = "w/rest.php/v1/transform/wikitext/to/html";
+        let mock_server = MockServer::start().await;
+        for (wikitext, html) in [("a", "<p>a</p>"), ("b", "<p>b</p>")] {
+            Mock::given(method("POST"))
+                .and(path(mock_path))
+                .and(body_json(json!({ "wikitext": wikitext })))
+                .respond_with(ResponseTemplate::new(200).set_body_string(html))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let cache = TransformCache::new();
+
+        let results: Vec<(String, Result<String, RestApiError>)> =
+            Transform::wikitext2html_many(&api, ["a", "b", "a"], &cache, 2, true)
+                .collect()
+                .await;
+        let results: Vec<(&str, &str)> = results
+            .iter()
+            .map(|(wikitext, html)| {
+                (
+                    wikitext.as_str(),
+                    html.as_deref().expect("Failed to transform"),
+                )
+            })
+            .collect();
+        assert_eq!(results, [("a", "<p>a</p>"), ("b", "<p>b</p>")]);
+        assert_eq!(cache.len().await, 2);
+
+        // Served from the cache, the mocks expect a single request each
+        let cached: Vec<_> = Transform::wikitext2html_many(&api, ["b"], &cache, 2, false)
+            .collect()
+            .await;
+        assert_eq!(cached[0].1.as_deref().ok(), Some("<p>b</p>"));
+        cache.clear().await;
+        assert!(cache.is_empty().await);
+    }
+
+    #[tokio::test]
+    async fn test_transform_cache_capacity() {
+        let cache = TransformCache::with_capacity(2);
+        assert_eq!(cache.capacity(), 2);
+        for key in ["a", "b", "a", "c"] {
+            cache.insert(key.to_string(), key.to_uppercase()).await;
+        }
+        // The oldest entry is evicted first, even if it was replaced since
+        assert_eq!(cache.len().await, 2);
+        assert_eq!(cache.get("a").await, None);
+        assert_eq!(cache.get("b").await.as_deref(), Some("B"));
+        assert_eq!(cache.get("c").await.as_deref(), Some("C"));
+    }
+
+    #[tokio::test]
+    async fn test_html2wikitext_many() {
+        let mock_path = "w/rest.php/v1/transform/html/to/wikitext";
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(mock_path))
+            .and(body_json(json!({ "html": "<p>a</p>" })))
+            .respond_with(ResponseTemplate::new(200).set_body_string("a"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(mock_path))
+            .and(body_json(json!({ "html": "<p>broken</p>" })))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+        let cache = TransformCache::new();
+        for _ in 0..2 {
+            let results: HashMap<String, Result<String, RestApiError>> =
+                Transform::html2wikitext_many(
+                    &api,
+                    ["<p>a</p>", "<p>broken</p>"],
+                    &cache,
+                    4,
+                    false,
+                )
+                .collect()
+                .await;
+            assert_eq!(results["<p>a</p>"].as_deref().ok(), Some("a"));
+            assert!(results["<p>broken</p>"].is_err());
+        }
+        // Errors are not cached
+        assert_eq!(cache.len().await, 1);
+    }
+
     #[tokio::test]
     async fn test_html2wikitext_title() {
         let expected_wikitext = "!{{FULLPAGENAME}}?";
@@ -310,6 +825,145 @@ mod tests {
         assert_eq!(wikitext.trim(), expected_wikitext.trim());
     }
 
+    #[tokio::test]
+    async fn test_html2wikitext_selser() {
+        let title = "Talk:Foo/Bar";
+        let revision = RevisionId(12345);
+        let original_html: String = std::fs::read_to_string("test_data/wikitext2html_title.html")
+            .expect("Test file missing");
+        let html = original_html.replace("?", "!");
+        let expected_wikitext = "!{{FULLPAGENAME}}!";
+
+        // Set up mock server
+        let mock_path = format!(
+            "w/rest.php/v1/transform/html/to/wikitext/{}/{revision}",
+            encode(title)
+        );
+        let mock_server = MockServer::start().await;
+        let body = json!({
+            "html": html,
+            "original": {
+                "revid": revision,
+                "html": { "body": original_html }
+            }
+        });
+        Mock::given(method("POST"))
+            .and(path(mock_path))
+            .and(body_partial_json(body))
+            .and(header(reqwest::header::IF_MATCH, "\"12345/abc\""))
+            .and(header(reqwest::header::ACCEPT, "text/plain"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(expected_wikitext))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+
+        let wikitext = Transform::html2wikitext_selser(
+            html,
+            title,
+            revision,
+            &original_html,
+            Some("\"12345/abc\""),
+            &api,
+        )
+        .await
+        .expect("Failed to transform HTML to wikitext");
+        assert_eq!(wikitext, expected_wikitext);
+    }
+
+    #[tokio::test]
+    async fn test_wikitext2html_with() {
+        let title = "Talk:Foo/Bar";
+        let revision = RevisionId(12345);
+        let profile =
+            "text/html; charset=utf-8; profile=\"https://www.mediawiki.org/wiki/Specs/HTML/2.8.0\"";
+        let mock_path = format!(
+            "w/rest.php/v1/transform/wikitext/to/html/{}/{revision}",
+            encode(title)
+        );
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(mock_path))
+            .and(body_json(
+                json!({ "wikitext": "''a''", "body_only": true, "stash": true }),
+            ))
+            .and(header(reqwest::header::ACCEPT, profile))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("<p><i>a</i></p>")
+                    .insert_header("ETag", "\"12345/abc\""),
+            )
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+
+        let options = TransformOptions::new()
+            .with_title(title)
+            .with_revision(revision)
+            .with_body_only(true)
+            .with_stash(true)
+            .with_html_profile("2.8.0");
+        let (html, etag) = Transform::wikitext2html_with("''a''", &options, &api)
+            .await
+            .expect("Failed to transform wikitext to HTML");
+        assert_eq!(html, "<p><i>a</i></p>");
+        assert_eq!(etag.as_deref(), Some("\"12345/abc\""));
+
+        let untitled = TransformOptions::new().with_revision(revision);
+        let result = Transform::wikitext2html_with("''a''", &untitled, &api).await;
+        assert!(matches!(
+            result,
+            Err(RestApiError::TransformTitleRequired(RevisionId(12345)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_html2wikitext_with() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("w/rest.php/v1/transform/html/to/wikitext/Foo"))
+            .and(body_json(json!({
+                "html": "<p>b</p>",
+                "original": {
+                    "html": {
+                        "headers": { "content-type": PARSOID_HTML_CONTENT_TYPE },
+                        "body": "<p>a</p>"
+                    }
+                }
+            })))
+            .and(header(reqwest::header::IF_MATCH, "\"1/abc\""))
+            .and(header(
+                reqwest::header::ACCEPT,
+                "text/plain; charset=utf-8; profile=\"https://www.mediawiki.org/wiki/Specs/wikitext/1.0.0\"",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_string("b"))
+            .mount(&mock_server)
+            .await;
+        let api = RestApi::builder(&(mock_server.uri() + "/w/rest.php"))
+            .expect("Failed to create RestApi")
+            .build();
+
+        let options = TransformOptions::new()
+            .with_title("Foo")
+            .with_original_html("<p>a</p>", Some("\"1/abc\""))
+            .with_wikitext_profile("1.0.0");
+        let wikitext = Transform::html2wikitext_with("<p>b</p>", &options, &api)
+            .await
+            .expect("Failed to transform HTML to wikitext");
+        assert_eq!(wikitext, "b");
+
+        let content_type =
+            "text/html; charset=utf-8; profile=\"https://www.mediawiki.org/wiki/Specs/HTML/2.6.0\"";
+        let older = options.with_original_content_type(content_type);
+        assert_eq!(
+            older.body("html", "<p>b</p>".to_string())["original"]["html"]["headers"]["content-type"],
+            content_type
+        );
+    }
+
     #[tokio::test]
     async fn test_html2lint() {
         let wikitext = "== Hello Jupiter ==[[Link|text]]<table><table>";
@@ -1,20 +1,28 @@
+use crate::{
+    error::RestApiError,
+    id::{PageId, RevisionId, UserId},
+    timestamp::Timestamp,
+};
 use core::fmt;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use sha1::{Digest, Sha1};
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LicenseModel {
     pub url: String,
     pub title: String,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RevisionTimestamp {
-    pub id: usize,
-    pub timestamp: String,
+    pub id: RevisionId,
+    pub timestamp: Timestamp,
 }
 
-#[derive(Copy, Clone, Debug, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum HtmlFlavor {
     View,
     Stash,
@@ -33,7 +41,8 @@ impl fmt::Display for HtmlFlavor {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LanguageLink {
     pub code: String,
     pub name: String,
@@ -41,19 +50,22 @@ pub struct LanguageLink {
     pub title: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UserInfo {
-    pub id: Option<usize>,
+    pub id: Option<UserId>,
     pub name: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FileRevision {
-    pub timestamp: String,
+    pub timestamp: Timestamp,
     pub user: UserInfo,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MediaType {
     pub mediatype: String,
     pub size: Option<usize>,
@@ -63,90 +75,188 @@ pub struct MediaType {
     pub url: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FileInfo {
     pub title: String,
     pub file_description_url: String,
     pub latest: FileRevision,
     pub preferred: MediaType,
     pub original: MediaType,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub thumbnail: Option<MediaType>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
-pub struct TemplateInfo {
-    pub name: String,
-}
-
-#[derive(Clone, Debug, Deserialize)]
-pub struct Lint {
-    #[serde(alias = "type")]
-    pub type_name: String,
-    pub dsr: Vec<Option<usize>>,
-    #[serde(alias = "templateInfo")]
-    pub template_info: Option<TemplateInfo>,
-    pub params: Value,
-}
-
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RevisionPageInfo {
-    pub id: usize,
+    pub id: PageId,
     pub key: String,
     pub title: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RevisionInfo {
-    pub id: usize,
+    pub id: RevisionId,
     pub size: usize,
     pub delta: isize,
     pub comment: String,
     pub minor: bool,
-    pub timestamp: String,
+    pub timestamp: Timestamp,
     pub content_model: String,
     pub page: RevisionPageInfo,
     pub license: LicenseModel,
     pub user: UserInfo,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DiffOffset {
     pub from: Option<usize>,
     pub to: Option<usize>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// The type of a line in a `Diff`, as used by the `MediaWiki` diff format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffLineType {
+    Context,
+    Added,
+    Deleted,
+    Changed,
+    MovedFrom,
+    MovedTo,
+    Unknown(usize),
+}
+
+impl From<usize> for DiffLineType {
+    fn from(type_id: usize) -> Self {
+        match type_id {
+            0 => Self::Context,
+            1 => Self::Added,
+            2 => Self::Deleted,
+            3 => Self::Changed,
+            4 => Self::MovedFrom,
+            5 => Self::MovedTo,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl DiffLineType {
+    /// Returns the numeric type ID, as used by the `MediaWiki` diff format.
+    pub const fn type_id(&self) -> usize {
+        match self {
+            Self::Context => 0,
+            Self::Added => 1,
+            Self::Deleted => 2,
+            Self::Changed => 3,
+            Self::MovedFrom => 4,
+            Self::MovedTo => 5,
+            Self::Unknown(type_id) => *type_id,
+        }
+    }
+}
+
+/// The type of a highlighted range within a changed line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HighlightType {
+    Added,
+    Deleted,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct HighlightRange {
+    /// Byte offset of the range in the line text
+    pub start: usize,
+    /// Length of the range, in bytes
+    pub length: usize,
+    #[serde(rename = "type", alias = "type_id")]
+    pub type_id: usize,
+}
+
+impl HighlightRange {
+    /// Returns the type of the highlighted range.
+    pub const fn highlight_type(&self) -> HighlightType {
+        match self.type_id {
+            0 => HighlightType::Added,
+            _ => HighlightType::Deleted,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MoveInfo {
+    pub id: String,
+    #[serde(rename = "linkId", alias = "link_id")]
+    pub link_id: String,
+    #[serde(rename = "linkDirection", alias = "link_direction")]
+    pub link_direction: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DiffInfo {
-    #[serde(alias = "lineNumber")]
+    #[serde(
+        rename = "lineNumber",
+        alias = "line_number",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub line_number: Option<usize>,
     pub offset: DiffOffset,
     pub text: String,
-    #[serde(alias = "type")]
+    #[serde(rename = "type", alias = "type_id")]
     pub type_id: usize,
+    #[serde(
+        default,
+        rename = "highlightRanges",
+        alias = "highlight_ranges",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub highlight_ranges: Vec<HighlightRange>,
+    #[serde(
+        rename = "moveInfo",
+        alias = "move_info",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub move_info: Option<MoveInfo>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+impl DiffInfo {
+    /// Returns the type of the line.
+    pub fn line_type(&self) -> DiffLineType {
+        DiffLineType::from(self.type_id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DiffSection {
     pub heading: String,
     pub level: usize,
     pub offset: usize,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DiffSections {
-    pub id: usize,
+    pub id: RevisionId,
     pub sections: Vec<DiffSection>,
     pub slot_role: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Diff {
     pub diff: Vec<DiffInfo>,
     pub from: DiffSections,
     pub to: DiffSections,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum Filter {
     Anonymous,
     Bot,
@@ -165,7 +275,8 @@ impl fmt::Display for Filter {
     }
 }
 
-#[derive(Clone, Copy, Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum HistoryFilterExtended {
     Anonymous,
     Temporary,
@@ -196,31 +307,111 @@ impl fmt::Display for HistoryFilterExtended {
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HistoryRevisionInfo {
-    pub id: usize,
+    pub id: RevisionId,
     pub size: usize,
     pub delta: isize,
     pub comment: String,
     pub minor: bool,
-    pub timestamp: String,
+    pub timestamp: Timestamp,
     pub user: UserInfo,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct History {
     pub revisions: Vec<HistoryRevisionInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub latest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub older: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newer: Option<String>,
+}
+
+impl History {
+    /// Returns the `older_than` revision ID from the URL of the next older history segment, if any.
+    pub fn older_than(&self) -> Option<RevisionId> {
+        Self::url_parameter(self.older.as_deref()?, "older_than")
+    }
+
+    /// Returns the `newer_than` revision ID from the URL of the next newer history segment, if any.
+    pub fn newer_than(&self) -> Option<RevisionId> {
+        Self::url_parameter(self.newer.as_deref()?, "newer_than")
+    }
+
+    /// Returns the revision ID value of a URL query parameter.
+    fn url_parameter(url: &str, key: &str) -> Option<RevisionId> {
+        let (_, query) = url.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|part| part.split_once('='))
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.parse().ok())
+            .map(RevisionId)
+    }
+}
+
+/// A bound of a revision range, either a revision ID or a timestamp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevisionBound {
+    Id(RevisionId),
+    Timestamp(Timestamp),
 }
 
-#[derive(Clone, Copy, Debug, Deserialize)]
+impl RevisionBound {
+    /// Compares a revision to the bound.
+    pub fn cmp_revision(&self, revision: &HistoryRevisionInfo) -> std::cmp::Ordering {
+        self.cmp(revision.id, &revision.timestamp)
+    }
+
+    /// Compares a revision, given by its ID and timestamp, to the bound.
+    pub(crate) fn cmp(&self, id: RevisionId, timestamp: &Timestamp) -> std::cmp::Ordering {
+        match self {
+            RevisionBound::Id(bound) => id.cmp(bound),
+            RevisionBound::Timestamp(bound) => timestamp.cmp(bound),
+        }
+    }
+}
+
+impl From<RevisionId> for RevisionBound {
+    fn from(id: RevisionId) -> Self {
+        Self::Id(id)
+    }
+}
+
+impl TryFrom<&str> for RevisionBound {
+    type Error = RestApiError;
+
+    fn try_from(timestamp: &str) -> Result<Self, Self::Error> {
+        Ok(Self::Timestamp(timestamp.parse()?))
+    }
+}
+
+impl From<Timestamp> for RevisionBound {
+    fn from(timestamp: Timestamp) -> Self {
+        Self::Timestamp(timestamp)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for RevisionBound {
+    fn from(datetime: chrono::DateTime<chrono::Utc>) -> Self {
+        Self::Timestamp(datetime.into())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct HistoryCounts {
     pub count: usize,
     pub limit: bool,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Thumbnail {
     pub mimetype: String,
     pub width: Option<usize>,
@@ -229,9 +420,10 @@ pub struct Thumbnail {
     pub url: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SearchResult {
-    pub id: usize,
+    pub id: PageId,
     pub key: String,
     pub title: String,
     pub excerpt: Option<String>,
@@ -241,25 +433,29 @@ pub struct SearchResult {
     pub thumbnail: Option<Thumbnail>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SearchResults {
     pub pages: Vec<SearchResult>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PopupInfo {
     pub title: String,
     pub contentmodel: String,
     pub pagelanguage: String,
     pub pagelanguagehtmlcode: String,
+    pub pagelanguagedir: String,
     pub extract: String,
     pub canonicalurl: String,
     pub fullurl: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PageInfo {
-    pub id: usize,
+    pub id: PageId,
     pub key: String,
     pub title: String,
     pub latest: RevisionTimestamp,
@@ -267,7 +463,148 @@ pub struct PageInfo {
     pub license: LicenseModel,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MediaResult {
     pub files: Vec<FileInfo>,
 }
+
+/// Returns the SHA-1 hash of a revision content, as hex string.
+pub fn content_hash(content: &str) -> String {
+    Sha1::digest(content.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Quotes a CSV field if necessary.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lint::Lint;
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+
+    /// The response of a `bare` endpoint: the info, with the HTML URL that `get_bare` returns next to it.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Bare<T> {
+        #[serde(flatten)]
+        info: T,
+        html_url: String,
+    }
+
+    /// Checks that the serialized JSON is identical to the original JSON.
+    fn assert_same(serialized: &Value, original: &Value, key_path: &str) {
+        match (serialized, original) {
+            (Value::Object(serialized), Value::Object(original)) => {
+                for (key, value) in original {
+                    match serialized.get(key) {
+                        Some(serialized_value) => {
+                            assert_same(serialized_value, value, &format!("{key_path}.{key}"));
+                        }
+                        None => panic!("Missing key {key_path}.{key}"),
+                    }
+                }
+                for key in serialized.keys() {
+                    assert!(
+                        original.contains_key(key),
+                        "Unexpected key {key_path}.{key}"
+                    );
+                }
+            }
+            (Value::Array(serialized), Value::Array(original)) => {
+                assert_eq!(serialized.len(), original.len(), "Length of {key_path}");
+                for (num, (a, b)) in serialized.iter().zip(original).enumerate() {
+                    assert_same(a, b, &format!("{key_path}[{num}]"));
+                }
+            }
+            _ => assert_eq!(serialized, original, "Value of {key_path}"),
+        }
+    }
+
+    fn assert_round_trip<T: Serialize + DeserializeOwned + PartialEq + fmt::Debug>(
+        test_file: &str,
+    ) {
+        let test_text: String =
+            std::fs::read_to_string(format!("test_data/{test_file}")).expect("Test file missing");
+        let original: Value = serde_json::from_str(&test_text).expect("Failed to parse JSON");
+        let parsed: T = serde_json::from_value(original.clone()).expect("Failed to deserialize");
+        let serialized = serde_json::to_value(&parsed).expect("Failed to serialize");
+        assert_same(&serialized, &original, test_file);
+        let reparsed: T = serde_json::from_value(serialized).expect("Failed to deserialize");
+        assert_eq!(reparsed, parsed);
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash("test"),
+            "a94a8fe5ccb19ba61c4c0873d391e987982fbbd3"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_round_trip::<Bare<PageInfo>>("page_get_bare.json");
+        assert_round_trip::<Bare<RevisionInfo>>("revision_get_bare.json");
+        assert_round_trip::<History>("page_history.json");
+        assert_round_trip::<HistoryCounts>("page_history_counts.json");
+        assert_round_trip::<Diff>("revision_compare.json");
+        assert_round_trip::<FileInfo>("file_get.json");
+        assert_round_trip::<SearchResults>("search_page.json");
+        assert_round_trip::<SearchResults>("search_title.json");
+        assert_round_trip::<Vec<Lint>>("revision_lint.json");
+        assert_round_trip::<Vec<Lint>>("wikitext2lint.json");
+        assert_round_trip::<Vec<Lint>>("page_lint.json");
+        assert_round_trip::<Vec<LanguageLink>>("page_links_language.json");
+        assert_round_trip::<MediaResult>("page_links_media.json");
+        assert_round_trip::<PopupInfo>("math_popup_html.json");
+    }
+
+    #[test]
+    fn test_serialize_renamed_fields() {
+        let info: DiffInfo = serde_json::from_value(serde_json::json!({
+            "type": 3,
+            "lineNumber": 5,
+            "text": "foo",
+            "offset": { "from": 0, "to": 0 },
+            "highlightRanges": [{ "start": 0, "length": 3, "type": 0 }]
+        }))
+        .expect("Failed to deserialize");
+        let json = serde_json::to_value(&info).expect("Failed to serialize");
+        assert_eq!(json["lineNumber"], 5);
+        assert_eq!(json["type"], 3);
+        assert_eq!(json["highlightRanges"][0]["type"], 0);
+        assert!(json.get("moveInfo").is_none());
+        // Field names are accepted as well
+        let info2: DiffInfo = serde_json::from_value(serde_json::json!({
+            "type_id": 3,
+            "line_number": 5,
+            "text": "foo",
+            "offset": { "from": 0, "to": 0 },
+            "highlight_ranges": [{ "start": 0, "length": 3, "type_id": 0 }]
+        }))
+        .expect("Failed to deserialize");
+        assert_eq!(info2, info);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_json_schema() {
+        let page_schema = serde_json::to_value(schemars::schema_for!(PageInfo))
+            .expect("Failed to serialize schema");
+        assert_eq!(page_schema["title"], "PageInfo");
+        assert!(page_schema["properties"].get("latest").is_some());
+        let diff_schema = serde_json::to_value(schemars::schema_for!(DiffInfo))
+            .expect("Failed to serialize schema");
+        assert!(diff_schema["properties"].get("lineNumber").is_some());
+    }
+}
@@ -0,0 +1,1239 @@
+use crate::section::OPAQUE_TAGS;
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+/// Extension tags whose content is parsed on its own, so eg a `|` in a `<ref>` does not split template parameters.
+const EXTENSION_TAGS: &[&str] = &[
+    "ref",
+    "references",
+    "gallery",
+    "poem",
+    "indicator",
+    "imagemap",
+    "inputbox",
+    "categorytree",
+    "mapframe",
+    "maplink",
+    "section",
+];
+
+/// Tags that never have content.
+const VOID_TAGS: &[&str] = &["br", "hr", "wbr"];
+
+/// URL prefixes of external links.
+const URL_SCHEMES: &[&str] = &[
+    "http://", "https://", "ftp://", "ftps://", "sftp://", "irc://", "ircs://", "git://", "svn://",
+    "mailto:", "news:", "//",
+];
+
+/// A range of UTF-8 byte offsets in the wikitext.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub const fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub const fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub const fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Checks if `other` lies within this span.
+    pub const fn contains(&self, other: &Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+}
+
+/// A heading, eg `== History ==`. The span covers the whole line, without the line break.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heading {
+    pub span: Span,
+    pub level: usize,
+    /// The heading text without the `=` markup and comments, trimmed
+    pub title: String,
+    /// The span between the `=` markup
+    pub content_span: Span,
+    pub content: Vec<WikitextNode>,
+}
+
+/// A template call, eg `{{Cite web |url=... }}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    pub span: Span,
+    /// The template name as written, without comments, trimmed
+    pub name: String,
+    pub name_span: Span,
+    pub params: Vec<Parameter>,
+}
+
+impl Template {
+    /// Returns the parameter with the given name, or position for unnamed parameters.
+    /// As in `MediaWiki`, the last one wins if a parameter is given more than once.
+    pub fn param(&self, name: &str) -> Option<&Parameter> {
+        self.params.iter().rev().find(|param| param.name == name)
+    }
+}
+
+/// A template parameter. The span starts after the `|`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Parameter {
+    pub span: Span,
+    /// The parameter name without comments, trimmed, or its position (from `1`) for unnamed parameters
+    pub name: String,
+    /// The span of the name before the `=`, or `None` for unnamed parameters
+    pub name_span: Option<Span>,
+    pub value_span: Span,
+    pub value: Vec<WikitextNode>,
+}
+
+/// A template argument, eg `{{{1|default}}}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Argument {
+    pub span: Span,
+    pub name: String,
+    pub name_span: Span,
+    pub default_span: Option<Span>,
+    pub default: Vec<WikitextNode>,
+}
+
+/// An internal link, eg `[[Target|text]]`, including file and category links.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Link {
+    pub span: Span,
+    /// The link target as written, without comments, trimmed
+    pub target: String,
+    pub target_span: Span,
+    /// The span after the first `|`, or `None` for links without text
+    pub text_span: Option<Span>,
+    pub text: Vec<WikitextNode>,
+}
+
+impl Link {
+    /// Returns the category name, eg `Foo` for `[[Category:Foo]]`, if this is a category link.
+    pub fn category(&self) -> Option<&str> {
+        let (namespace, name) = self.target.split_once(':')?;
+        namespace
+            .trim()
+            .eq_ignore_ascii_case("category")
+            .then(|| name.trim())
+    }
+}
+
+/// An external link in brackets, eg `[https://example.org text]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalLink {
+    pub span: Span,
+    pub url: String,
+    pub text_span: Option<Span>,
+    pub text: Vec<WikitextNode>,
+}
+
+/// An HTML or extension tag, eg `<ref name="x">...</ref>`, `<nowiki>...</nowiki>` or `<br/>`.
+/// The content of tags like `<nowiki>` and `<pre>` is a single text node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tag {
+    pub span: Span,
+    /// The tag name, in lowercase
+    pub name: String,
+    pub attrs: HashMap<String, String>,
+    pub attrs_span: Span,
+    /// The span between the opening and closing tag, or `None` for self-closing and unclosed tags
+    pub content_span: Option<Span>,
+    pub content: Vec<WikitextNode>,
+}
+
+impl Tag {
+    /// Returns the value of an attribute.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.get(name).map(String::as_str)
+    }
+}
+
+/// A table, eg `{| class="wikitable" ... |}`. Tables that are not closed end where their parent ends.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Table {
+    pub span: Span,
+    pub attrs_span: Span,
+    pub caption: Option<TableCaption>,
+    pub rows: Vec<TableRow>,
+}
+
+/// A table caption (`|+`). The span starts after the `|+`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableCaption {
+    pub span: Span,
+    pub content: Vec<WikitextNode>,
+}
+
+/// A table row. Cells before the first `|-` form a row without attributes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableRow {
+    pub span: Span,
+    pub attrs_span: Option<Span>,
+    pub cells: Vec<TableCell>,
+}
+
+/// A table cell. The span starts after the `|`, `!`, `||` or `!!` cell marker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableCell {
+    pub span: Span,
+    pub header: bool,
+    pub attrs_span: Option<Span>,
+    pub content_span: Span,
+    pub content: Vec<WikitextNode>,
+}
+
+/// A node of parsed wikitext.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WikitextNode {
+    Text(Span),
+    /// A comment, eg `<!-- ... -->`
+    Comment(Span),
+    Heading(Heading),
+    Template(Template),
+    Argument(Argument),
+    Link(Link),
+    ExternalLink(ExternalLink),
+    Tag(Tag),
+    Table(Table),
+}
+
+impl WikitextNode {
+    pub const fn span(&self) -> Span {
+        match self {
+            Self::Text(span) | Self::Comment(span) => *span,
+            Self::Heading(heading) => heading.span,
+            Self::Template(template) => template.span,
+            Self::Argument(argument) => argument.span,
+            Self::Link(link) => link.span,
+            Self::ExternalLink(link) => link.span,
+            Self::Tag(tag) => tag.span,
+            Self::Table(table) => table.span,
+        }
+    }
+
+    /// Returns the direct child nodes, in source order.
+    pub fn children(&self) -> Vec<&Self> {
+        match self {
+            Self::Text(_) | Self::Comment(_) => vec![],
+            Self::Heading(heading) => heading.content.iter().collect(),
+            Self::Template(template) => template
+                .params
+                .iter()
+                .flat_map(|param| &param.value)
+                .collect(),
+            Self::Argument(argument) => argument.default.iter().collect(),
+            Self::Link(link) => link.text.iter().collect(),
+            Self::ExternalLink(link) => link.text.iter().collect(),
+            Self::Tag(tag) => tag.content.iter().collect(),
+            Self::Table(table) => table
+                .caption
+                .iter()
+                .flat_map(|caption| &caption.content)
+                .chain(
+                    table
+                        .rows
+                        .iter()
+                        .flat_map(|row| &row.cells)
+                        .flat_map(|cell| &cell.content),
+                )
+                .collect(),
+        }
+    }
+}
+
+/// Wikitext parsed into a tree of nodes with byte spans, without a server round trip.
+/// The top-level nodes cover the whole source without gaps, so every byte can be attributed to a node.
+/// Formatting like bold, lists and magic words is part of the text nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wikitext {
+    source: String,
+    nodes: Vec<WikitextNode>,
+}
+
+impl Wikitext {
+    pub fn parse<S: Into<String>>(source: S) -> Self {
+        let source = source.into();
+        let nodes = Parser::new(&source).parse_nodes(&[]);
+        Self { source, nodes }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn into_source(self) -> String {
+        self.source
+    }
+
+    /// Returns the top-level nodes.
+    pub fn nodes(&self) -> &[WikitextNode] {
+        &self.nodes
+    }
+
+    /// Returns the source of a span.
+    pub fn slice(&self, span: Span) -> &str {
+        &self.source[span.range()]
+    }
+
+    /// Returns all nodes, depth-first in source order.
+    pub fn descendants(&self) -> Vec<&WikitextNode> {
+        fn collect<'a>(nodes: &[&'a WikitextNode], ret: &mut Vec<&'a WikitextNode>) {
+            for node in nodes {
+                ret.push(node);
+                collect(&node.children(), ret);
+            }
+        }
+        let mut ret = vec![];
+        collect(&self.nodes.iter().collect::<Vec<_>>(), &mut ret);
+        ret
+    }
+
+    /// Returns all templates, including nested ones.
+    pub fn templates(&self) -> Vec<&Template> {
+        self.descendants()
+            .into_iter()
+            .filter_map(|node| match node {
+                WikitextNode::Template(template) => Some(template),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns all internal links, including those nested in templates and tags.
+    pub fn links(&self) -> Vec<&Link> {
+        self.descendants()
+            .into_iter()
+            .filter_map(|node| match node {
+                WikitextNode::Link(link) => Some(link),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns all category links, eg `[[Category:Foo|sort key]]`, but not links to categories like `[[:Category:Foo]]`.
+    pub fn categories(&self) -> Vec<&Link> {
+        self.links()
+            .into_iter()
+            .filter(|link| link.category().is_some())
+            .collect()
+    }
+
+    pub fn headings(&self) -> Vec<&Heading> {
+        self.descendants()
+            .into_iter()
+            .filter_map(|node| match node {
+                WikitextNode::Heading(heading) => Some(heading),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns all tags with the given (lowercase) name, eg `ref`.
+    pub fn tags(&self, name: &str) -> Vec<&Tag> {
+        self.descendants()
+            .into_iter()
+            .filter_map(|node| match node {
+                WikitextNode::Tag(tag) if tag.name == name => Some(tag),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn tables(&self) -> Vec<&Table> {
+        self.descendants()
+            .into_iter()
+            .filter_map(|node| match node {
+                WikitextNode::Table(table) => Some(table),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the spans of all comments.
+    pub fn comments(&self) -> Vec<Span> {
+        self.descendants()
+            .into_iter()
+            .filter_map(|node| match node {
+                WikitextNode::Comment(span) => Some(*span),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Markup at which `Parser::parse_nodes` stops.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Stop {
+    Pipe,
+    /// A `|` that is not part of `||`
+    SinglePipe,
+    Equals,
+    TemplateEnd,
+    ArgumentEnd,
+    LinkEnd,
+    ExternalLinkEnd,
+    LineEnd,
+    TagClose(String),
+    /// A line starting with `|` or `!`, after optional whitespace
+    TableLine,
+    CellSeparator,
+    HeaderSeparator,
+}
+
+/// Markup that `Parser::attempt` can fail to parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Construct {
+    Argument,
+    Template,
+    Link,
+    ExternalLink,
+    Tag,
+}
+
+/// A recursive descent parser. Constructs that are not closed are parsed as text.
+struct Parser<'a> {
+    source: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    end: usize,
+    /// Constructs that failed to parse at a position (and end), so they are not tried again
+    failed: HashSet<(Construct, usize, usize)>,
+    /// The content of an unclosed HTML tag, which belongs to the parent of the tag
+    pending: Vec<WikitextNode>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            bytes: source.as_bytes(),
+            pos: 0,
+            end: source.len(),
+            failed: HashSet::new(),
+            pending: vec![],
+        }
+    }
+
+    /// Parses nodes until one of `stops` matches, or the end.
+    fn parse_nodes(&mut self, stops: &[Stop]) -> Vec<WikitextNode> {
+        let mut nodes = vec![];
+        let mut text_start: Option<usize> = None;
+        while self.pos < self.end {
+            if stops.iter().any(|stop| self.matches(stop)) {
+                break;
+            }
+            let start = self.pos;
+            if let Some(node) = self.parse_markup(stops) {
+                if let Some(text_start) = text_start.take() {
+                    nodes.push(WikitextNode::Text(Span::new(text_start, start)));
+                }
+                nodes.push(node);
+                nodes.append(&mut self.pending);
+                continue;
+            }
+            text_start.get_or_insert(start);
+            self.advance_char();
+        }
+        if let Some(text_start) = text_start {
+            nodes.push(WikitextNode::Text(Span::new(text_start, self.pos)));
+        }
+        nodes
+    }
+
+    /// Parses a node at the current position, or returns `None` and leaves the position unchanged.
+    fn parse_markup(&mut self, stops: &[Stop]) -> Option<WikitextNode> {
+        let tail = self.tail();
+        if tail.starts_with("<!--") {
+            let end = tail.find("-->").map_or(self.end, |end| self.pos + end + 3);
+            let span = Span::new(self.pos, end);
+            self.pos = end;
+            return Some(WikitextNode::Comment(span));
+        }
+        if self.at_line_start() {
+            if tail.starts_with('=')
+                && let Some(heading) = self.parse_heading()
+            {
+                return Some(WikitextNode::Heading(heading));
+            }
+            if tail.trim_start_matches([' ', '\t']).starts_with("{|") {
+                // A nested table owns its lines, so the stops of the enclosing table do not apply
+                let outer: Vec<Stop> = stops
+                    .iter()
+                    .filter(|stop| {
+                        !matches!(
+                            stop,
+                            Stop::TableLine
+                                | Stop::CellSeparator
+                                | Stop::HeaderSeparator
+                                | Stop::SinglePipe
+                        )
+                    })
+                    .cloned()
+                    .collect();
+                return Some(WikitextNode::Table(self.parse_table(&outer)));
+            }
+        }
+        if tail.starts_with("{{{")
+            && let Some(argument) = self.attempt(Construct::Argument, Self::parse_argument)
+        {
+            return Some(WikitextNode::Argument(argument));
+        }
+        if tail.starts_with("{{") {
+            return self
+                .attempt(Construct::Template, Self::parse_template)
+                .map(WikitextNode::Template);
+        }
+        if tail.starts_with("[[") {
+            return self
+                .attempt(Construct::Link, Self::parse_link)
+                .map(WikitextNode::Link);
+        }
+        if tail.starts_with('[') && URL_SCHEMES.iter().any(|s| tail[1..].starts_with(s)) {
+            return self
+                .attempt(Construct::ExternalLink, Self::parse_external_link)
+                .map(WikitextNode::ExternalLink);
+        }
+        if tail.starts_with('<') {
+            let stops = stops.to_vec();
+            return self
+                .attempt(Construct::Tag, |parser| parser.parse_tag(&stops))
+                .map(WikitextNode::Tag);
+        }
+        None
+    }
+
+    /// Runs a parse function, and resets the position if it fails.
+    /// A construct is not tried without its closing markup, nor again at a position where it failed,
+    /// so unclosed markup does not take exponential time.
+    /// Parse functions do not depend on the stops of the caller, except for HTML tags, which never fail.
+    fn attempt<T>(
+        &mut self,
+        construct: Construct,
+        f: impl FnOnce(&mut Self) -> Option<T>,
+    ) -> Option<T> {
+        let key = (construct, self.pos, self.end);
+        if self.failed.contains(&key) || !self.closable(construct) {
+            return None;
+        }
+        let ret = f(self);
+        if ret.is_none() {
+            self.pos = key.1;
+            self.failed.insert(key);
+        }
+        ret
+    }
+
+    fn parse_heading(&mut self) -> Option<Heading> {
+        let start = self.pos;
+        let line_end = self.line_end();
+        let mut line = &self.source[start..line_end];
+        // Trailing whitespace and comments are allowed after the closing "="
+        loop {
+            line = line.trim_end();
+            match line.strip_suffix("-->").and_then(|l| l.rfind("<!--")) {
+                Some(comment) => line = &line[..comment],
+                None => break,
+            }
+        }
+        let opening = line.len() - line.trim_start_matches('=').len();
+        let closing = line.len() - line.trim_end_matches('=').len();
+        if opening == line.len() {
+            return None;
+        }
+        let level = opening.min(closing).min(6);
+        if level == 0 {
+            return None;
+        }
+        let content_span = Span::new(start + level, start + line.len() - level);
+        let content = self.parse_within(content_span, &[]);
+        self.pos = line_end;
+        Some(Heading {
+            span: Span::new(start, line_end),
+            level,
+            title: self.plain_text(&content),
+            content_span,
+            content,
+        })
+    }
+
+    fn parse_template(&mut self) -> Option<Template> {
+        let start = self.pos;
+        self.pos += 2;
+        let name_start = self.pos;
+        let name_nodes = self.parse_nodes(&[Stop::Pipe, Stop::TemplateEnd]);
+        let name_span = Span::new(name_start, self.pos);
+        let mut params = vec![];
+        let mut position = 0;
+        while self.matches(&Stop::Pipe) {
+            self.pos += 1;
+            let param_start = self.pos;
+            let first = self.parse_nodes(&[Stop::Pipe, Stop::TemplateEnd, Stop::Equals]);
+            let param = if self.matches(&Stop::Equals) {
+                let param_name_span = Span::new(param_start, self.pos);
+                self.pos += 1;
+                let value_start = self.pos;
+                let value = self.parse_nodes(&[Stop::Pipe, Stop::TemplateEnd]);
+                Parameter {
+                    span: Span::new(param_start, self.pos),
+                    name: self.plain_text(&first),
+                    name_span: Some(param_name_span),
+                    value_span: Span::new(value_start, self.pos),
+                    value,
+                }
+            } else {
+                position += 1;
+                Parameter {
+                    span: Span::new(param_start, self.pos),
+                    name: position.to_string(),
+                    name_span: None,
+                    value_span: Span::new(param_start, self.pos),
+                    value: first,
+                }
+            };
+            params.push(param);
+        }
+        if !self.matches(&Stop::TemplateEnd) {
+            return None;
+        }
+        self.pos += 2;
+        Some(Template {
+            span: Span::new(start, self.pos),
+            name: self.plain_text(&name_nodes),
+            name_span,
+            params,
+        })
+    }
+
+    fn parse_argument(&mut self) -> Option<Argument> {
+        let start = self.pos;
+        self.pos += 3;
+        let name_start = self.pos;
+        let name_nodes = self.parse_nodes(&[Stop::Pipe, Stop::ArgumentEnd]);
+        let name_span = Span::new(name_start, self.pos);
+        let (default_span, default) = if self.matches(&Stop::Pipe) {
+            self.pos += 1;
+            let default_start = self.pos;
+            let default = self.parse_nodes(&[Stop::ArgumentEnd]);
+            (Some(Span::new(default_start, self.pos)), default)
+        } else {
+            (None, vec![])
+        };
+        if !self.matches(&Stop::ArgumentEnd) {
+            return None;
+        }
+        self.pos += 3;
+        Some(Argument {
+            span: Span::new(start, self.pos),
+            name: self.plain_text(&name_nodes),
+            name_span,
+            default_span,
+            default,
+        })
+    }
+
+    fn parse_link(&mut self) -> Option<Link> {
+        let start = self.pos;
+        self.pos += 2;
+        let target_start = self.pos;
+        let target_nodes = self.parse_nodes(&[Stop::Pipe, Stop::LinkEnd, Stop::LineEnd]);
+        let target_span = Span::new(target_start, self.pos);
+        let (text_span, text) = if self.matches(&Stop::Pipe) {
+            self.pos += 1;
+            let text_start = self.pos;
+            let text = self.parse_nodes(&[Stop::LinkEnd]);
+            (Some(Span::new(text_start, self.pos)), text)
+        } else {
+            (None, vec![])
+        };
+        if !self.matches(&Stop::LinkEnd) {
+            return None;
+        }
+        self.pos += 2;
+        Some(Link {
+            span: Span::new(start, self.pos),
+            target: self.plain_text(&target_nodes),
+            target_span,
+            text_span,
+            text,
+        })
+    }
+
+    fn parse_external_link(&mut self) -> Option<ExternalLink> {
+        let start = self.pos;
+        self.pos += 1;
+        let url_len = self
+            .tail()
+            .find(|c: char| c.is_whitespace() || matches!(c, ']' | '[' | '<' | '>' | '"'))
+            .unwrap_or(self.end - self.pos);
+        let url = self.source[self.pos..self.pos + url_len].to_string();
+        self.pos += url_len;
+        let spaces = self.tail().len() - self.tail().trim_start_matches([' ', '\t']).len();
+        self.pos += spaces;
+        let (text_span, text) = if self.matches(&Stop::ExternalLinkEnd) {
+            (None, vec![])
+        } else {
+            let text_start = self.pos;
+            let text = self.parse_nodes(&[Stop::ExternalLinkEnd, Stop::LineEnd]);
+            (Some(Span::new(text_start, self.pos)), text)
+        };
+        if !self.matches(&Stop::ExternalLinkEnd) {
+            return None;
+        }
+        self.pos += 1;
+        Some(ExternalLink {
+            span: Span::new(start, self.pos),
+            url,
+            text_span,
+            text,
+        })
+    }
+
+    fn parse_tag(&mut self, stops: &[Stop]) -> Option<Tag> {
+        let start = self.pos;
+        let tail = self.tail();
+        let name_len = tail[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+            .unwrap_or(tail.len() - 1);
+        if name_len == 0 || !tail[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let name = tail[1..=name_len].to_ascii_lowercase();
+        let after_name = &tail[1 + name_len..];
+        if !after_name.starts_with(['>', '/', ' ', '\t', '\n']) {
+            return None;
+        }
+        let tag_end = after_name
+            .find(['>', '<'])
+            .filter(|&end| after_name[end..].starts_with('>'))?;
+        let attrs_text = &after_name[..tag_end];
+        let self_closing = attrs_text.trim_end().ends_with('/');
+        let attrs_start = start + 1 + name_len;
+        let attrs_span = Span::new(attrs_start, attrs_start + attrs_text.len());
+        let attrs = parse_attributes(attrs_text.trim_end().trim_end_matches('/'));
+        self.pos = attrs_span.end + 1;
+        let mut tag = Tag {
+            span: Span::new(start, self.pos),
+            name,
+            attrs,
+            attrs_span,
+            content_span: None,
+            content: vec![],
+        };
+        if self_closing || VOID_TAGS.contains(&tag.name.as_str()) {
+            return Some(tag);
+        }
+        let content_start = self.pos;
+        let close = Stop::TagClose(tag.name.clone());
+        if OPAQUE_TAGS.contains(&tag.name.as_str()) {
+            let closing = format!("</{}", tag.name);
+            let close_pos = self.tail().to_ascii_lowercase().find(&closing)?;
+            self.pos += close_pos;
+            let content_span = Span::new(content_start, self.pos);
+            tag.content = vec![WikitextNode::Text(content_span)];
+            tag.content_span = Some(content_span);
+        } else if EXTENSION_TAGS.contains(&tag.name.as_str()) {
+            if !self.has_close_tag(&tag.name) {
+                return None;
+            }
+            tag.content = self.parse_nodes(std::slice::from_ref(&close));
+            if !self.matches(&close) {
+                return None;
+            }
+            tag.content_span = Some(Span::new(content_start, self.pos));
+        } else if !self.has_close_tag(&tag.name) {
+            // Unclosed HTML tag: its content is parsed by the parent
+            return Some(tag);
+        } else {
+            let mut inner_stops = stops.to_vec();
+            inner_stops.push(close.clone());
+            let content = self.parse_nodes(&inner_stops);
+            if !self.matches(&close) {
+                // Unclosed HTML tag: its content becomes part of the parent. The content stopped at a stop
+                // of the parent (or the end), so it is the same as if the parent had parsed it.
+                self.pending = content;
+                return Some(tag);
+            }
+            tag.content = content;
+            tag.content_span = Some(Span::new(content_start, self.pos));
+        }
+        self.pos = self
+            .tail()
+            .find('>')
+            .map_or(self.end, |end| self.pos + end + 1);
+        tag.span.end = self.pos;
+        Some(tag)
+    }
+
+    fn parse_table(&mut self, outer: &[Stop]) -> Table {
+        let start = self.pos;
+        self.skip_spaces();
+        self.pos += 2;
+        let line_end = self.line_end();
+        let attrs_span = Span::new(self.pos, line_end);
+        self.pos = line_end;
+        let mut table = Table {
+            span: Span::new(start, self.pos),
+            attrs_span,
+            caption: None,
+            rows: vec![],
+        };
+        let mut row: Option<TableRow> = None;
+        let mut line_stops = outer.to_vec();
+        line_stops.push(Stop::TableLine);
+        loop {
+            if self.tail().starts_with('\n') {
+                self.pos += 1;
+            }
+            if self.pos >= self.end || outer.iter().any(|stop| self.matches(stop)) {
+                break;
+            }
+            let line_start = self.pos;
+            self.skip_spaces();
+            let tail = self.tail();
+            if tail.starts_with("|}") {
+                self.pos += 2;
+                break;
+            } else if tail.starts_with("|-") {
+                table.rows.extend(row.take());
+                self.pos += 1;
+                let dashes = self.tail().len() - self.tail().trim_start_matches('-').len();
+                self.pos += dashes;
+                let row_end = self.line_end();
+                row = Some(TableRow {
+                    span: Span::new(line_start, row_end),
+                    attrs_span: Some(Span::new(self.pos, row_end)),
+                    cells: vec![],
+                });
+                self.pos = row_end;
+            } else if tail.starts_with("|+") {
+                self.pos += 2;
+                let caption_start = self.pos;
+                let content = self.parse_nodes(&line_stops);
+                table.caption = Some(TableCaption {
+                    span: Span::new(caption_start, self.pos),
+                    content,
+                });
+            } else if tail.starts_with(['|', '!']) {
+                let header = tail.starts_with('!');
+                self.pos += 1;
+                let row = row.get_or_insert_with(|| TableRow {
+                    span: Span::new(line_start, line_start),
+                    attrs_span: None,
+                    cells: vec![],
+                });
+                loop {
+                    row.cells.push(self.parse_table_cell(header, outer));
+                    row.span.end = self.pos;
+                    if self.matches(&Stop::CellSeparator)
+                        || (header && self.matches(&Stop::HeaderSeparator))
+                    {
+                        self.pos += 2;
+                    } else {
+                        break;
+                    }
+                }
+            } else {
+                // Text outside of cells is not rendered in the table
+                self.pos = self.line_end();
+            }
+        }
+        table.rows.extend(row);
+        table.span.end = self.pos;
+        table
+    }
+
+    fn parse_table_cell(&mut self, header: bool, outer: &[Stop]) -> TableCell {
+        let start = self.pos;
+        let mut stops = outer.to_vec();
+        stops.extend([Stop::TableLine, Stop::CellSeparator]);
+        if header {
+            stops.push(Stop::HeaderSeparator);
+        }
+        let mut attr_stops = stops.clone();
+        attr_stops.push(Stop::SinglePipe);
+        let mut content = self.parse_nodes(&attr_stops);
+        let mut attrs_span = None;
+        let mut content_start = start;
+        if self.matches(&Stop::SinglePipe) {
+            if self.source[start..self.pos].contains('\n') {
+                // A "|" in a continuation line is part of the content
+                let rest = self.parse_nodes(&stops);
+                extend_merged(&mut content, rest);
+            } else {
+                attrs_span = Some(Span::new(start, self.pos));
+                self.pos += 1;
+                content_start = self.pos;
+                content = self.parse_nodes(&stops);
+            }
+        }
+        TableCell {
+            span: Span::new(start, self.pos),
+            header,
+            attrs_span,
+            content_span: Span::new(content_start, self.pos),
+            content,
+        }
+    }
+
+    // ____________________________________________________________________________________________________
+    // Helpers
+
+    fn matches(&self, stop: &Stop) -> bool {
+        let tail = self.tail();
+        match stop {
+            Stop::Pipe => tail.starts_with('|'),
+            Stop::SinglePipe => tail.starts_with('|') && !tail.starts_with("||"),
+            Stop::Equals => tail.starts_with('='),
+            Stop::TemplateEnd => tail.starts_with("}}"),
+            Stop::ArgumentEnd => tail.starts_with("}}}"),
+            Stop::LinkEnd => tail.starts_with("]]"),
+            Stop::ExternalLinkEnd => tail.starts_with(']'),
+            Stop::LineEnd => tail.starts_with('\n'),
+            Stop::TagClose(name) => is_close_tag(tail, name),
+            Stop::TableLine => {
+                self.at_line_start() && tail.trim_start_matches([' ', '\t']).starts_with(['|', '!'])
+            }
+            Stop::CellSeparator => tail.starts_with("||"),
+            Stop::HeaderSeparator => tail.starts_with("!!"),
+        }
+    }
+
+    /// Checks if the closing markup of a construct at the current position occurs before the end.
+    /// Without it, the construct can not be parsed, so its content is not parsed either.
+    fn closable(&self, construct: Construct) -> bool {
+        let (offset, closer) = match construct {
+            Construct::Argument => (3, "}}}"),
+            Construct::Template => (2, "}}"),
+            Construct::Link => (2, "]]"),
+            Construct::ExternalLink => (1, "]"),
+            Construct::Tag => return true,
+        };
+        self.tail()
+            .get(offset..)
+            .is_some_and(|rest| rest.contains(closer))
+    }
+
+    /// Checks if a closing tag for `name` occurs before the end.
+    fn has_close_tag(&self, name: &str) -> bool {
+        let tail = self.tail();
+        tail.match_indices("</")
+            .any(|(offset, _)| is_close_tag(&tail[offset..], name))
+    }
+
+    /// Parses the nodes within `span`, and moves to its end.
+    fn parse_within(&mut self, span: Span, stops: &[Stop]) -> Vec<WikitextNode> {
+        let end = self.end;
+        self.pos = span.start;
+        self.end = span.end;
+        let ret = self.parse_nodes(stops);
+        self.end = end;
+        self.pos = span.end;
+        ret
+    }
+
+    /// Returns the source of nodes without comments, trimmed.
+    fn plain_text(&self, nodes: &[WikitextNode]) -> String {
+        nodes
+            .iter()
+            .filter(|node| !matches!(node, WikitextNode::Comment(_)))
+            .map(|node| &self.source[node.span().range()])
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    fn tail(&self) -> &'a str {
+        &self.source[self.pos..self.end]
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.pos == 0 || self.bytes[self.pos - 1] == b'\n'
+    }
+
+    /// Returns the position of the next line break, or the end.
+    fn line_end(&self) -> usize {
+        self.tail()
+            .find('\n')
+            .map_or(self.end, |end| self.pos + end)
+    }
+
+    fn skip_spaces(&mut self) {
+        let tail = self.tail();
+        self.pos += tail.len() - tail.trim_start_matches([' ', '\t']).len();
+    }
+
+    fn advance_char(&mut self) {
+        self.pos += self.tail().chars().next().map_or(1, char::len_utf8);
+    }
+}
+
+/// Checks if `tail` starts with a closing tag for `name`.
+fn is_close_tag(tail: &str, name: &str) -> bool {
+    tail.strip_prefix("</").is_some_and(|after| {
+        after.len() >= name.len()
+            && after.is_char_boundary(name.len())
+            && after[..name.len()].eq_ignore_ascii_case(name)
+            && after[name.len()..].starts_with(['>', ' ', '\t', '\n'])
+    })
+}
+
+/// Appends nodes, merging adjacent text nodes.
+fn extend_merged(nodes: &mut Vec<WikitextNode>, rest: Vec<WikitextNode>) {
+    for node in rest {
+        if let (Some(WikitextNode::Text(last)), WikitextNode::Text(span)) =
+            (nodes.last_mut(), &node)
+            && last.end == span.start
+        {
+            last.end = span.end;
+            continue;
+        }
+        nodes.push(node);
+    }
+}
+
+/// Parses HTML-style attributes like `name="foo" group=note`.
+pub(crate) fn parse_attributes(tag: &str) -> HashMap<String, String> {
+    let mut ret = HashMap::new();
+    let mut tail = tag.trim_start();
+    while let Some(eq) = tail.find('=') {
+        let key = tail[..eq].trim().to_lowercase();
+        let after = tail[eq + 1..].trim_start();
+        let (value, remaining) = match after.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let after = &after[1..];
+                let end = after.find(quote).unwrap_or(after.len());
+                (&after[..end], after.get(end + 1..).unwrap_or_default())
+            }
+            _ => {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        ret.insert(key, value.trim().to_string());
+        tail = remaining.trim_start();
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_source() -> String {
+        let text = std::fs::read_to_string("test_data/page_get.json").expect("Test file missing");
+        let json: serde_json::Value = serde_json::from_str(&text).expect("Failed to parse JSON");
+        json["source"].as_str().expect("Source missing").to_string()
+    }
+
+    /// Checks that nodes cover `span` without gaps or overlaps.
+    fn assert_contiguous(nodes: &[WikitextNode], span: Span) {
+        let mut pos = span.start;
+        for node in nodes {
+            assert_eq!(node.span().start, pos);
+            pos = node.span().end;
+        }
+        assert_eq!(pos, span.end);
+    }
+
+    #[test]
+    fn test_parse_page() {
+        let source = test_source();
+        let wikitext = Wikitext::parse(source.clone());
+        assert_contiguous(wikitext.nodes(), Span::new(0, source.len()));
+        assert_eq!(wikitext.headings().len(), 52);
+        assert_eq!(wikitext.tags("ref").len(), 193);
+        assert_eq!(wikitext.templates().len(), 409);
+        assert_eq!(wikitext.links().len(), 447);
+        assert_eq!(wikitext.comments().len(), 11);
+        let infobox = wikitext
+            .templates()
+            .into_iter()
+            .find(|template| template.name == "Infobox programming language")
+            .expect("Infobox missing");
+        let name = infobox.param("name").expect("Parameter missing");
+        assert_eq!(wikitext.slice(name.value_span).trim(), "Rust");
+        assert_eq!(wikitext.slice(infobox.span), &source[infobox.span.range()]);
+        assert_eq!(wikitext.headings()[0].title, "History");
+        assert_eq!(wikitext.headings()[0].level, 2);
+    }
+
+    #[test]
+    fn test_templates() {
+        let wikitext = Wikitext::parse(
+            "a {{Foo <!-- x -->| bar | key = [[A|b]] {{Baz|1}} |2=x|{{{arg|def}}} }} {{broken",
+        );
+        let nodes = wikitext.nodes();
+        assert_eq!(nodes.len(), 3);
+        let WikitextNode::Template(template) = &nodes[1] else {
+            panic!("Template expected");
+        };
+        assert_eq!(template.name, "Foo");
+        assert_eq!(wikitext.slice(template.name_span), "Foo <!-- x -->");
+        let names: Vec<&str> = template.params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["1", "key", "2", "2"]);
+        assert_eq!(wikitext.slice(template.params[0].value_span), " bar ");
+        let key = template.param("key").expect("Parameter missing");
+        assert_eq!(
+            key.name_span.map(|span| wikitext.slice(span)),
+            Some(" key ")
+        );
+        assert_eq!(wikitext.slice(key.value_span), " [[A|b]] {{Baz|1}} ");
+        assert_eq!(
+            wikitext.slice(template.param("2").expect("Parameter missing").value_span),
+            "{{{arg|def}}} "
+        );
+        assert!(matches!(
+            template.params[3].value[0],
+            WikitextNode::Argument(_)
+        ));
+        assert_eq!(wikitext.templates().len(), 2);
+        assert_eq!(wikitext.slice(nodes[2].span()), " {{broken");
+    }
+
+    #[test]
+    fn test_links_and_tags() {
+        let source = "[[File:X.png|thumb|A [[b]] c]] [https://example.org Example] [//x.org] \
+            <ref name=\"r\">{{Cite web|title=a|b}}</ref><ref name=r /><br>\
+            <nowiki>{{not a template}}</nowiki><span>open <div>x|y</div> [[Unclosed\n<pre>[[x]]</pre>";
+        let wikitext = Wikitext::parse(source);
+        assert_contiguous(wikitext.nodes(), Span::new(0, source.len()));
+        let links = wikitext.links();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].target, "File:X.png");
+        assert_eq!(
+            links[0].text_span.map(|span| wikitext.slice(span)),
+            Some("thumb|A [[b]] c")
+        );
+        let external: Vec<&ExternalLink> = wikitext
+            .descendants()
+            .into_iter()
+            .filter_map(|node| match node {
+                WikitextNode::ExternalLink(link) => Some(link),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(external[0].url, "https://example.org");
+        assert_eq!(
+            external[0].text_span.map(|span| wikitext.slice(span)),
+            Some("Example")
+        );
+        assert_eq!(external[1].text_span, None);
+        let refs = wikitext.tags("ref");
+        assert_eq!(refs[0].attr("name"), Some("r"));
+        assert_eq!(
+            refs[0].content_span.map(|span| wikitext.slice(span)),
+            Some("{{Cite web|title=a|b}}")
+        );
+        assert_eq!(refs[1].content_span, None);
+        assert_eq!(wikitext.tags("br").len(), 1);
+        let nowiki = wikitext.tags("nowiki");
+        assert_eq!(
+            nowiki[0].content,
+            [WikitextNode::Text(
+                nowiki[0].content_span.expect("Content missing")
+            )]
+        );
+        assert_eq!(wikitext.templates().len(), 1);
+        let span = wikitext.tags("span");
+        assert_eq!(span[0].content_span, None);
+        assert_eq!(wikitext.tags("div")[0].content.len(), 1);
+        assert_eq!(wikitext.tags("pre").len(), 1);
+    }
+
+    #[test]
+    fn test_headings_and_comments() {
+        let source = "Lead\n== A ==\n=== B <!-- c --> === <!-- d -->\n= not a heading\nx == y ==\n";
+        let wikitext = Wikitext::parse(source);
+        let headings = wikitext.headings();
+        assert_eq!(headings.len(), 2);
+        assert_eq!(headings[0].title, "A");
+        assert_eq!(wikitext.slice(headings[0].span), "== A ==");
+        assert_eq!(headings[1].level, 3);
+        assert_eq!(headings[1].title, "B");
+        assert_eq!(wikitext.slice(headings[1].content_span), " B <!-- c --> ");
+        assert_eq!(wikitext.comments().len(), 1);
+    }
+
+    #[test]
+    fn test_tables() {
+        let source = "{| class=\"wikitable\"\n|+ Caption\n! A !! style=\"x\" | B\n|-\n| 1 || [[a|b]]\n|-  id=r\n| colspan=2 |\nmulti\nline | pipe\n{|\n| nested\n|}\n|}\nafter";
+        let wikitext = Wikitext::parse(source);
+        assert_contiguous(wikitext.nodes(), Span::new(0, source.len()));
+        let tables = wikitext.tables();
+        assert_eq!(tables.len(), 2);
+        let table = tables[0];
+        assert_eq!(wikitext.slice(table.attrs_span), " class=\"wikitable\"");
+        assert_eq!(
+            table
+                .caption
+                .as_ref()
+                .map(|caption| wikitext.slice(caption.span)),
+            Some(" Caption\n")
+        );
+        assert_eq!(table.rows.len(), 3);
+        let header = &table.rows[0].cells;
+        assert!(header.iter().all(|cell| cell.header));
+        assert_eq!(wikitext.slice(header[0].content_span), " A ");
+        assert_eq!(
+            header[1].attrs_span.map(|span| wikitext.slice(span)),
+            Some(" style=\"x\" ")
+        );
+        assert_eq!(wikitext.slice(header[1].content_span), " B\n");
+        let cells = &table.rows[1].cells;
+        assert_eq!(cells.len(), 2);
+        assert_eq!(wikitext.slice(cells[1].content_span), " [[a|b]]\n");
+        assert_eq!(cells[1].attrs_span, None);
+        let row = &table.rows[2];
+        assert_eq!(
+            row.attrs_span.map(|span| wikitext.slice(span)),
+            Some("  id=r")
+        );
+        assert_eq!(row.cells.len(), 1);
+        assert_eq!(
+            wikitext.slice(row.cells[0].content_span),
+            "\nmulti\nline | pipe\n{|\n| nested\n|}\n"
+        );
+        assert_eq!(wikitext.slice(tables[1].span), "{|\n| nested\n|}");
+        assert_eq!(wikitext.slice(wikitext.nodes()[1].span()), "\nafter");
+    }
+
+    #[test]
+    fn test_table_in_template() {
+        let source = "{{Foo|\n{{{!}}\n{|\n| a\n}}b";
+        let wikitext = Wikitext::parse(source);
+        assert_contiguous(wikitext.nodes(), Span::new(0, source.len()));
+        let templates = wikitext.templates();
+        assert_eq!(templates[0].name, "Foo");
+        assert_eq!(templates[0].params.len(), 2);
+        assert_eq!(wikitext.tables().len(), 1);
+    }
+
+    #[test]
+    fn test_unclosed_markup() {
+        // Every opener is tried at most once, rather than once per enclosing opener
+        let start = std::time::Instant::now();
+        for opener in ["{{", "[[", "<b>", "<span>{{a|", "{{{", "[https://x.org "] {
+            let source = opener.repeat(1000);
+            let wikitext = Wikitext::parse(source.as_str());
+            assert_contiguous(wikitext.nodes(), Span::new(0, source.len()));
+            assert!(wikitext.templates().is_empty());
+            assert!(wikitext.links().is_empty());
+        }
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+}
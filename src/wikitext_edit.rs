@@ -0,0 +1,310 @@
+use crate::{
+    utilities::Diff,
+    wikitext::{Link, Span, Template, Wikitext, WikitextNode},
+};
+
+/// A replacement of a span of the original wikitext. Insertions have an empty span.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WikitextEdit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// Collects changes to parsed wikitext, and applies them to the source.
+/// Every byte outside of the changed spans is kept as it is, so the result only differs where a change was made.
+/// Changes refer to the spans of the original wikitext; a change that overlaps an earlier one is rejected.
+#[derive(Clone, Debug)]
+pub struct WikitextEditor<'a> {
+    wikitext: &'a Wikitext,
+    edits: Vec<WikitextEdit>,
+    /// Normalized names of categories that were inserted, or renamed to
+    added_categories: Vec<String>,
+    /// Normalized names of categories that were renamed
+    removed_categories: Vec<String>,
+    /// Whether a category link was inserted at the end of an uncategorized wikitext
+    appended_category: bool,
+}
+
+impl<'a> WikitextEditor<'a> {
+    pub const fn new(wikitext: &'a Wikitext) -> Self {
+        Self {
+            wikitext,
+            edits: vec![],
+            added_categories: vec![],
+            removed_categories: vec![],
+            appended_category: false,
+        }
+    }
+
+    pub const fn wikitext(&self) -> &'a Wikitext {
+        self.wikitext
+    }
+
+    /// Returns the changes made so far, in the order they were made.
+    pub fn edits(&self) -> &[WikitextEdit] {
+        &self.edits
+    }
+
+    /// Returns `true` if no changes were made.
+    pub const fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Replaces the source of `span`. Returns `false` if the span is not valid for the source,
+    /// or overlaps an earlier change. Replacing a span with its own source is not recorded as a change.
+    pub fn replace<S: Into<String>>(&mut self, span: Span, replacement: S) -> bool {
+        let source = self.wikitext.source();
+        if span.start > span.end || source.get(span.range()).is_none() {
+            return false;
+        }
+        if self.edits.iter().any(|edit| overlaps(&edit.span, &span)) {
+            return false;
+        }
+        let replacement = replacement.into();
+        if source[span.range()] != replacement {
+            self.edits.push(WikitextEdit { span, replacement });
+        }
+        true
+    }
+
+    /// Inserts text at a byte offset. Insertions at the same offset are applied in the order they were made.
+    pub fn insert<S: Into<String>>(&mut self, pos: usize, text: S) -> bool {
+        self.replace(Span::new(pos, pos), text)
+    }
+
+    /// Replaces a whole template call, eg with another template call or its expansion.
+    pub fn replace_template<S: Into<String>>(
+        &mut self,
+        template: &Template,
+        replacement: S,
+    ) -> bool {
+        self.replace(template.span, replacement)
+    }
+
+    /// Renames the named parameter `old` of a template call, keeping the whitespace around the name.
+    /// Returns the number of renamed parameters, which can be more than one if the parameter is given repeatedly.
+    pub fn rename_param(&mut self, template: &Template, old: &str, new: &str) -> usize {
+        let name_spans: Vec<Span> = template
+            .params
+            .iter()
+            .filter(|param| param.name == old)
+            .filter_map(|param| param.name_span)
+            .collect();
+        name_spans
+            .into_iter()
+            .filter(|span| self.replace_trimmed(*span, new))
+            .count()
+    }
+
+    /// Changes the target of an internal link, keeping the whitespace around the target and the link text.
+    pub fn set_link_target(&mut self, link: &Link, target: &str) -> bool {
+        self.replace_trimmed(link.target_span, target)
+    }
+
+    /// Renames the category `old` in all category links, keeping the namespace prefix as written and the sort keys.
+    /// Returns the number of changed links.
+    pub fn rename_category(&mut self, old: &str, new: &str) -> usize {
+        let old = normalize_title(old);
+        let links: Vec<&Link> = self
+            .wikitext
+            .categories()
+            .into_iter()
+            .filter(|link| link.category().map(normalize_title).as_ref() == Some(&old))
+            .collect();
+        let renamed = links
+            .into_iter()
+            .filter(|link| {
+                let target = self.wikitext.slice(link.target_span).trim();
+                let (namespace, _) = target.split_once(':').unwrap_or(("Category", ""));
+                self.set_link_target(link, &format!("{namespace}:{new}"))
+            })
+            .count();
+        if renamed > 0 {
+            self.removed_categories.push(old);
+            self.added_categories.push(normalize_title(new));
+        }
+        renamed
+    }
+
+    /// Adds a category link after the last top-level category link, or at the end of the wikitext.
+    /// Categories inserted later follow on their own lines.
+    /// Returns `false` if the page already is in the category, including categories inserted or renamed by this editor.
+    pub fn insert_category(&mut self, name: &str, sort_key: Option<&str>) -> bool {
+        let normalized = normalize_title(name);
+        let in_original = !self.removed_categories.contains(&normalized)
+            && self
+                .wikitext
+                .categories()
+                .iter()
+                .any(|link| link.category().map(normalize_title).as_ref() == Some(&normalized));
+        if in_original || self.added_categories.contains(&normalized) {
+            return false;
+        }
+        let category = sort_key.map_or_else(
+            || format!("[[Category:{name}]]"),
+            |sort_key| format!("[[Category:{name}|{sort_key}]]"),
+        );
+        let last = self
+            .wikitext
+            .nodes()
+            .iter()
+            .filter_map(|node| match node {
+                WikitextNode::Link(link) if link.category().is_some() => Some(link.span.end),
+                _ => None,
+            })
+            .next_back();
+        let inserted = match last {
+            Some(pos) => self.insert(pos, format!("\n{category}")),
+            None => {
+                let pos = self.wikitext.source().trim_end().len();
+                let separator = match (pos, self.appended_category) {
+                    (_, true) => "\n",
+                    (0, false) => "",
+                    _ => "\n\n",
+                };
+                let appended = self.insert(pos, format!("{separator}{category}"));
+                self.appended_category |= appended;
+                appended
+            }
+        };
+        if inserted {
+            self.added_categories.push(normalized);
+        }
+        inserted
+    }
+
+    /// Returns the source with all changes applied.
+    pub fn apply(&self) -> String {
+        let source = self.wikitext.source();
+        let mut edits: Vec<&WikitextEdit> = self.edits.iter().collect();
+        edits.sort_by_key(|edit| (edit.span.start, edit.span.end));
+        let mut ret = String::with_capacity(source.len());
+        let mut last_end = 0;
+        for edit in edits {
+            ret += &source[last_end..edit.span.start];
+            ret += &edit.replacement;
+            last_end = edit.span.end;
+        }
+        ret += &source[last_end..];
+        ret
+    }
+
+    /// Returns a local diff from the original to the changed wikitext.
+    pub fn diff(&self) -> Diff {
+        Diff::from_wikitext(self.wikitext.source(), &self.apply())
+    }
+
+    // ____________________________________________________________________________________________________
+    // Private functions
+
+    /// Replaces the source of `span` without its leading and trailing whitespace.
+    fn replace_trimmed(&mut self, span: Span, replacement: &str) -> bool {
+        let text = self.wikitext.slice(span);
+        let start = span.start + text.len() - text.trim_start().len();
+        let end = span.end - (text.len() - text.trim_end().len());
+        self.replace(Span::new(start, end.max(start)), replacement)
+    }
+}
+
+/// Checks if two changes overlap. An insertion overlaps a replacement only if it lies strictly inside of it.
+fn overlaps(a: &Span, b: &Span) -> bool {
+    if a.is_empty() {
+        (b.start + 1..b.end).contains(&a.start)
+    } else if b.is_empty() {
+        (a.start + 1..a.end).contains(&b.start)
+    } else {
+        a.start < b.end && b.start < a.end
+    }
+}
+
+/// Normalizes a page title for comparison: underscores as spaces, and the first letter in uppercase.
+fn normalize_title(title: &str) -> String {
+    let title = title.trim().replace('_', " ");
+    let mut chars = title.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untouched_bytes() {
+        let source = "{{Foo | accessdate = 2020 |x=1}}\n[[ Bar |text]] {{Foo|accessdate=1}}\n";
+        let wikitext = Wikitext::parse(source);
+        let templates = wikitext.templates();
+        let mut editor = WikitextEditor::new(&wikitext);
+        assert_eq!(
+            editor.rename_param(templates[0], "accessdate", "access-date"),
+            1
+        );
+        assert!(editor.set_link_target(wikitext.links()[0], "Baz"));
+        assert!(editor.replace_template(templates[1], "{{Bar}}"));
+        assert_eq!(
+            editor.apply(),
+            "{{Foo | access-date = 2020 |x=1}}\n[[ Baz |text]] {{Bar}}\n"
+        );
+        assert_eq!(editor.edits().len(), 3);
+        // Overlaps the replaced template
+        assert_eq!(editor.rename_param(templates[1], "accessdate", "x"), 0);
+        assert!(!editor.replace(Span::new(0, source.len() + 1), ""));
+    }
+
+    #[test]
+    fn test_no_change() {
+        let wikitext = Wikitext::parse("[[Foo]]");
+        let mut editor = WikitextEditor::new(&wikitext);
+        assert!(editor.set_link_target(wikitext.links()[0], "Foo"));
+        assert!(editor.is_empty());
+        assert_eq!(editor.apply(), "[[Foo]]");
+    }
+
+    #[test]
+    fn test_categories() {
+        let wikitext = Wikitext::parse(
+            "Text [[:Category:Old]]\n\n[[category:old_name|Key]]\n[[Category:B]]\n",
+        );
+        let mut editor = WikitextEditor::new(&wikitext);
+        assert_eq!(editor.rename_category("Old name", "New name"), 1);
+        assert!(!editor.insert_category("b", None));
+        assert!(!editor.insert_category("New_name", None));
+        assert!(editor.insert_category("C", Some("Key")));
+        assert_eq!(
+            editor.apply(),
+            "Text [[:Category:Old]]\n\n[[category:New name|Key]]\n[[Category:B]]\n[[Category:C|Key]]\n"
+        );
+        let diff = editor.diff();
+        assert!(!diff.diff.is_empty());
+
+        let uncategorized = Wikitext::parse("Text\n");
+        let mut uncategorized_editor = WikitextEditor::new(&uncategorized);
+        assert!(uncategorized_editor.insert_category("A", None));
+        assert!(uncategorized_editor.insert_category("B", None));
+        assert!(!uncategorized_editor.insert_category("a", None));
+        assert_eq!(
+            uncategorized_editor.apply(),
+            "Text\n\n[[Category:A]]\n[[Category:B]]\n"
+        );
+    }
+
+    #[test]
+    fn test_page_roundtrip() {
+        let text = std::fs::read_to_string("test_data/page_get.json").expect("Test file missing");
+        let json: serde_json::Value = serde_json::from_str(&text).expect("Failed to parse JSON");
+        let source = json["source"].as_str().expect("Source missing");
+        let wikitext = Wikitext::parse(source);
+        let mut editor = WikitextEditor::new(&wikitext);
+        let templates = wikitext.templates();
+        let renamed: usize = templates
+            .iter()
+            .filter(|template| template.name.eq_ignore_ascii_case("cite web"))
+            .map(|template| editor.rename_param(template, "access-date", "accessdate"))
+            .sum();
+        assert!(renamed > 0);
+        let patched = editor.apply();
+        assert_eq!(patched.len(), source.len() - renamed);
+        assert_eq!(patched.replace("accessdate", "access-date"), source);
+    }
+}